@@ -0,0 +1,52 @@
+use super::Adjustment;
+use crate::filters::FilterParam;
+
+pub struct BrightnessContrast {
+    brightness: f64,
+    contrast: f64,
+}
+
+impl BrightnessContrast {
+    pub fn new() -> Self {
+        BrightnessContrast {
+            brightness: 0.0,
+            contrast: 0.0,
+        }
+    }
+}
+
+impl Adjustment for BrightnessContrast {
+    fn id(&self) -> &'static str {
+        "brightness-contrast"
+    }
+
+    fn name(&self) -> &'static str {
+        "Brightness-Contrast"
+    }
+
+    fn params(&self) -> Vec<FilterParam> {
+        vec![
+            FilterParam::new("brightness", "Brightness", -100.0, 100.0, 1.0, self.brightness),
+            FilterParam::new("contrast", "Contrast", -100.0, 100.0, 1.0, self.contrast),
+        ]
+    }
+
+    fn set_param(&mut self, key: &str, value: f64) {
+        match key {
+            "brightness" => self.brightness = value,
+            "contrast" => self.contrast = value,
+            _ => {}
+        }
+    }
+
+    fn adjust(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let brightness = (self.brightness / 200.0) as f32;
+        let contrast = (self.contrast / 100.0).clamp(-1.0, 0.99) as f32;
+        let slope = if contrast < 0.0 {
+            1.0 + contrast
+        } else {
+            1.0 / (1.0 - contrast)
+        };
+        rgb.map(|c| (c + brightness - 0.5) * slope + 0.5)
+    }
+}
@@ -0,0 +1,70 @@
+use super::Adjustment;
+use crate::{core::color::luminance, filters::FilterParam};
+
+const RANGES: [&str; 3] = ["Shadows", "Midtones", "Highlights"];
+const KEYS: [[&str; 3]; 3] = [
+    ["shadows-cyan-red", "shadows-magenta-green", "shadows-yellow-blue"],
+    ["midtones-cyan-red", "midtones-magenta-green", "midtones-yellow-blue"],
+    ["highlights-cyan-red", "highlights-magenta-green", "highlights-yellow-blue"],
+];
+
+pub struct ColorBalance {
+    // [faixa][canal], -100..100
+    values: [[f64; 3]; 3],
+}
+
+impl ColorBalance {
+    pub fn new() -> Self {
+        ColorBalance {
+            values: [[0.0; 3]; 3],
+        }
+    }
+}
+
+impl Adjustment for ColorBalance {
+    fn id(&self) -> &'static str {
+        "color-balance"
+    }
+
+    fn name(&self) -> &'static str {
+        "Color Balance"
+    }
+
+    fn params(&self) -> Vec<FilterParam> {
+        let mut params = Vec::new();
+        for (range, group) in RANGES.iter().enumerate() {
+            let v = self.values[range];
+            params.extend([
+                FilterParam::new(KEYS[range][0], "Cyan - Red", -100.0, 100.0, 1.0, v[0]),
+                FilterParam::new(KEYS[range][1], "Magenta - Green", -100.0, 100.0, 1.0, v[1]),
+                FilterParam::new(KEYS[range][2], "Yellow - Blue", -100.0, 100.0, 1.0, v[2]),
+            ]
+            .map(|param| param.in_group(group)));
+        }
+        params
+    }
+
+    fn set_param(&mut self, key: &str, value: f64) {
+        for (range, keys) in KEYS.iter().enumerate() {
+            if let Some(channel) = keys.iter().position(|k| *k == key) {
+                self.values[range][channel] = value;
+            }
+        }
+    }
+
+    fn adjust(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let l = luminance(rgb);
+        // Pesos suaves para sombras, meios-tons e realces.
+        let shadows = (1.0 - l).powi(2);
+        let highlights = l.powi(2);
+        let midtones = (1.0 - shadows - highlights).max(0.0);
+        let weights = [shadows, midtones, highlights];
+
+        [0, 1, 2].map(|c| {
+            let delta: f32 = (0..3)
+                .map(|range| weights[range] * (self.values[range][c] / 100.0) as f32 * 0.5)
+                .sum();
+            rgb[c] + delta
+        })
+    }
+}
@@ -0,0 +1,50 @@
+use super::Adjustment;
+use crate::geometry::Curve;
+
+const CHANNELS: [&str; 4] = ["Value", "Red", "Green", "Blue"];
+
+pub struct Curves {
+    curves: [Curve; 4],
+    luts: [[f32; 256]; 4],
+}
+
+impl Curves {
+    pub fn new() -> Self {
+        let curve = Curve::linear();
+        let lut = curve.lookup_table();
+        Curves {
+            curves: [curve.clone(), curve.clone(), curve.clone(), curve],
+            luts: [lut; 4],
+        }
+    }
+}
+
+impl Adjustment for Curves {
+    fn id(&self) -> &'static str {
+        "curves"
+    }
+
+    fn name(&self) -> &'static str {
+        "Curves"
+    }
+
+    fn curve_channels(&self) -> &'static [&'static str] {
+        &CHANNELS
+    }
+
+    fn curve(&self, channel: usize) -> Option<&Curve> {
+        self.curves.get(channel)
+    }
+
+    fn set_curve(&mut self, channel: usize, curve: Curve) {
+        if channel < self.curves.len() {
+            self.luts[channel] = curve.lookup_table();
+            self.curves[channel] = curve;
+        }
+    }
+
+    fn adjust(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let lookup = |lut: &[f32; 256], v: f32| lut[(v.clamp(0.0, 1.0) * 255.0).round() as usize];
+        [0, 1, 2].map(|c| lookup(&self.luts[0], lookup(&self.luts[c + 1], rgb[c])))
+    }
+}
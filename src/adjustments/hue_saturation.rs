@@ -0,0 +1,66 @@
+use super::Adjustment;
+use crate::{
+    core::color::{hsl_to_rgb, rgb_to_hsl},
+    filters::FilterParam,
+};
+
+pub struct HueSaturation {
+    hue: f64,
+    saturation: f64,
+    lightness: f64,
+}
+
+impl HueSaturation {
+    pub fn new() -> Self {
+        HueSaturation {
+            hue: 0.0,
+            saturation: 0.0,
+            lightness: 0.0,
+        }
+    }
+}
+
+impl Adjustment for HueSaturation {
+    fn id(&self) -> &'static str {
+        "hue-saturation"
+    }
+
+    fn name(&self) -> &'static str {
+        "Hue-Saturation"
+    }
+
+    fn params(&self) -> Vec<FilterParam> {
+        vec![
+            FilterParam::new("hue", "Hue", -180.0, 180.0, 1.0, self.hue),
+            FilterParam::new("saturation", "Saturation", -100.0, 100.0, 1.0, self.saturation),
+            FilterParam::new("lightness", "Lightness", -100.0, 100.0, 1.0, self.lightness),
+        ]
+    }
+
+    fn set_param(&mut self, key: &str, value: f64) {
+        match key {
+            "hue" => self.hue = value,
+            "saturation" => self.saturation = value,
+            "lightness" => self.lightness = value,
+            _ => {}
+        }
+    }
+
+    fn adjust(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let [h, s, l] = rgb_to_hsl(rgb);
+        let saturation = (self.saturation / 100.0) as f32;
+        let lightness = (self.lightness / 100.0) as f32;
+
+        let s = if saturation > 0.0 {
+            s + (1.0 - s) * saturation
+        } else {
+            s * (1.0 + saturation)
+        };
+        let l = if lightness > 0.0 {
+            l + (1.0 - l) * lightness
+        } else {
+            l * (1.0 + lightness)
+        };
+        hsl_to_rgb([h + self.hue as f32, s.clamp(0.0, 1.0), l.clamp(0.0, 1.0)])
+    }
+}
@@ -0,0 +1,95 @@
+use super::Adjustment;
+use crate::filters::FilterParam;
+
+const CHANNELS: [&str; 4] = ["Value", "Red", "Green", "Blue"];
+
+#[derive(Clone, Copy)]
+struct ChannelLevels {
+    in_black: f64,
+    in_white: f64,
+    gamma: f64,
+    out_black: f64,
+    out_white: f64,
+}
+
+impl ChannelLevels {
+    const IDENTITY: ChannelLevels = ChannelLevels {
+        in_black: 0.0,
+        in_white: 255.0,
+        gamma: 1.0,
+        out_black: 0.0,
+        out_white: 255.0,
+    };
+
+    fn map(&self, value: f32) -> f32 {
+        let range = (self.in_white - self.in_black).max(1.0);
+        let v = ((value as f64 * 255.0 - self.in_black) / range).clamp(0.0, 1.0);
+        let v = v.powf(1.0 / self.gamma.max(0.01));
+        ((self.out_black + v * (self.out_white - self.out_black)) / 255.0) as f32
+    }
+}
+
+pub struct Levels {
+    channels: [ChannelLevels; 4],
+}
+
+impl Levels {
+    pub fn new() -> Self {
+        Levels {
+            channels: [ChannelLevels::IDENTITY; 4],
+        }
+    }
+}
+
+impl Adjustment for Levels {
+    fn id(&self) -> &'static str {
+        "levels"
+    }
+
+    fn name(&self) -> &'static str {
+        "Levels"
+    }
+
+    fn params(&self) -> Vec<FilterParam> {
+        let mut params = Vec::new();
+        for (i, group) in CHANNELS.iter().enumerate() {
+            let c = &self.channels[i];
+            params.extend([
+                FilterParam::new(KEYS[i][0], "Input black", 0.0, 255.0, 1.0, c.in_black),
+                FilterParam::new(KEYS[i][1], "Input white", 0.0, 255.0, 1.0, c.in_white),
+                FilterParam::new(KEYS[i][2], "Gamma", 0.1, 10.0, 0.01, c.gamma),
+                FilterParam::new(KEYS[i][3], "Output black", 0.0, 255.0, 1.0, c.out_black),
+                FilterParam::new(KEYS[i][4], "Output white", 0.0, 255.0, 1.0, c.out_white),
+            ]
+            .map(|param| param.in_group(group)));
+        }
+        params
+    }
+
+    fn set_param(&mut self, key: &str, value: f64) {
+        for (i, keys) in KEYS.iter().enumerate() {
+            if let Some(field) = keys.iter().position(|k| *k == key) {
+                let c = &mut self.channels[i];
+                match field {
+                    0 => c.in_black = value,
+                    1 => c.in_white = value,
+                    2 => c.gamma = value,
+                    3 => c.out_black = value,
+                    _ => c.out_white = value,
+                }
+            }
+        }
+    }
+
+    fn adjust(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let master = &self.channels[0];
+        [0, 1, 2].map(|c| master.map(self.channels[c + 1].map(rgb[c])))
+    }
+}
+
+const KEYS: [[&str; 5]; 4] = [
+    ["in-black", "in-white", "gamma", "out-black", "out-white"],
+    ["red-in-black", "red-in-white", "red-gamma", "red-out-black", "red-out-white"],
+    ["green-in-black", "green-in-white", "green-gamma", "green-out-black", "green-out-white"],
+    ["blue-in-black", "blue-in-white", "blue-gamma", "blue-out-black", "blue-out-white"],
+];
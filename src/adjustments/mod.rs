@@ -0,0 +1,115 @@
+mod brightness_contrast;
+pub use brightness_contrast::BrightnessContrast;
+
+mod hue_saturation;
+pub use hue_saturation::HueSaturation;
+
+mod levels;
+pub use levels::Levels;
+
+mod curves;
+pub use curves::Curves;
+
+mod color_balance;
+pub use color_balance::ColorBalance;
+
+mod simple;
+pub use simple::{Desaturate, Invert, Posterize, Threshold};
+
+use crate::{
+    core::pixel_buffer::PixelBuffer,
+    filters::{Filter, FilterParam},
+    geometry::Curve,
+};
+
+// Ajuste de cor aplicado pixel a pixel sobre RGB nao pre-multiplicado.
+pub trait Adjustment {
+    fn id(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    fn adjust(&self, rgb: [f32; 3]) -> [f32; 3];
+
+    fn params(&self) -> Vec<FilterParam> {
+        Vec::new()
+    }
+
+    fn set_param(&mut self, _key: &str, _value: f64) {}
+
+    fn curve_channels(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn curve(&self, _channel: usize) -> Option<&Curve> {
+        None
+    }
+
+    fn set_curve(&mut self, _channel: usize, _curve: Curve) {}
+}
+
+impl<T: Adjustment> Filter for T {
+    fn id(&self) -> &'static str {
+        Adjustment::id(self)
+    }
+
+    fn name(&self) -> &'static str {
+        Adjustment::name(self)
+    }
+
+    fn params(&self) -> Vec<FilterParam> {
+        Adjustment::params(self)
+    }
+
+    fn set_param(&mut self, key: &str, value: f64) {
+        Adjustment::set_param(self, key, value);
+    }
+
+    fn apply(&self, buffer: &mut PixelBuffer) {
+        // Os dados da Cairo sao pre-multiplicados: desfaz antes de ajustar.
+        let pixels: Vec<[f32; 4]> = buffer
+            .to_rgba_f32()
+            .into_iter()
+            .map(|[r, g, b, a]| {
+                if a <= 0.0 {
+                    return [0.0; 4];
+                }
+                let [r, g, b] = self.adjust([r / a, g / a, b / a]);
+                [
+                    r.clamp(0.0, 1.0) * a,
+                    g.clamp(0.0, 1.0) * a,
+                    b.clamp(0.0, 1.0) * a,
+                    a,
+                ]
+            })
+            .collect();
+        buffer.write_rgba_f32(&pixels);
+    }
+
+    fn curve_channels(&self) -> &'static [&'static str] {
+        Adjustment::curve_channels(self)
+    }
+
+    fn curve(&self, channel: usize) -> Option<&Curve> {
+        Adjustment::curve(self, channel)
+    }
+
+    fn set_curve(&mut self, channel: usize, curve: Curve) {
+        Adjustment::set_curve(self, channel, curve);
+    }
+}
+
+pub fn all() -> Vec<Box<dyn Filter>> {
+    vec![
+        Box::new(BrightnessContrast::new()),
+        Box::new(HueSaturation::new()),
+        Box::new(Levels::new()),
+        Box::new(Curves::new()),
+        Box::new(ColorBalance::new()),
+        Box::new(Invert),
+        Box::new(Desaturate),
+        Box::new(Posterize::new()),
+        Box::new(Threshold::new()),
+    ]
+}
+
+pub fn create(id: &str) -> Option<Box<dyn Filter>> {
+    all().into_iter().find(|adjustment| adjustment.id() == id)
+}
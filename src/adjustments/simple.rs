@@ -0,0 +1,114 @@
+use super::Adjustment;
+use crate::{core::color::luminance, filters::FilterParam};
+
+pub struct Invert;
+
+impl Adjustment for Invert {
+    fn id(&self) -> &'static str {
+        "invert"
+    }
+
+    fn name(&self) -> &'static str {
+        "Invert"
+    }
+
+    fn adjust(&self, rgb: [f32; 3]) -> [f32; 3] {
+        rgb.map(|c| 1.0 - c)
+    }
+}
+
+pub struct Desaturate;
+
+impl Adjustment for Desaturate {
+    fn id(&self) -> &'static str {
+        "desaturate"
+    }
+
+    fn name(&self) -> &'static str {
+        "Desaturate"
+    }
+
+    fn adjust(&self, rgb: [f32; 3]) -> [f32; 3] {
+        [luminance(rgb); 3]
+    }
+}
+
+pub struct Posterize {
+    levels: f64,
+}
+
+impl Posterize {
+    pub fn new() -> Self {
+        Posterize { levels: 4.0 }
+    }
+}
+
+impl Adjustment for Posterize {
+    fn id(&self) -> &'static str {
+        "posterize"
+    }
+
+    fn name(&self) -> &'static str {
+        "Posterize"
+    }
+
+    fn params(&self) -> Vec<FilterParam> {
+        vec![FilterParam::new("levels", "Levels", 2.0, 64.0, 1.0, self.levels)]
+    }
+
+    fn set_param(&mut self, key: &str, value: f64) {
+        if key == "levels" {
+            self.levels = value.round().max(2.0);
+        }
+    }
+
+    fn adjust(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let steps = (self.levels - 1.0) as f32;
+        rgb.map(|c| (c * steps).round() / steps)
+    }
+}
+
+pub struct Threshold {
+    low: f64,
+    high: f64,
+}
+
+impl Threshold {
+    pub fn new() -> Self {
+        Threshold {
+            low: 127.0,
+            high: 255.0,
+        }
+    }
+}
+
+impl Adjustment for Threshold {
+    fn id(&self) -> &'static str {
+        "threshold"
+    }
+
+    fn name(&self) -> &'static str {
+        "Threshold"
+    }
+
+    fn params(&self) -> Vec<FilterParam> {
+        vec![
+            FilterParam::new("low", "Low", 0.0, 255.0, 1.0, self.low),
+            FilterParam::new("high", "High", 0.0, 255.0, 1.0, self.high),
+        ]
+    }
+
+    fn set_param(&mut self, key: &str, value: f64) {
+        match key {
+            "low" => self.low = value,
+            "high" => self.high = value,
+            _ => {}
+        }
+    }
+
+    fn adjust(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let v = luminance(rgb) as f64 * 255.0;
+        let out = if v >= self.low && v <= self.high { 1.0 } else { 0.0 };
+        [out; 3]
+    }
+}
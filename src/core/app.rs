@@ -1,9 +1,20 @@
-use std::{fmt, str::FromStr};
+use std::{fmt, path::Path, str::FromStr};
 
+use anyhow::{Result, bail};
 use crate::{
-    core::{canvas::Canvas, event::AppEvents},
+    core::{
+        canvas::Canvas,
+        color::Color,
+        event::{AppEvents, Key, KeyEvent, Modifiers, PointerEvent},
+        pixel_buffer::PixelBuffer,
+    },
+    filters::{Filter, FilterParam},
+    geometry::{Curve, Point},
     program::ProgramState,
-    tools::{BrushTool, PanTool, RotateTool, ZoomTool},
+    tools::{
+        AssistantTool, BrushTool, CloneMode, CloneTool, ColorPickerTool, PanTool, RotateTool,
+        SelectTool, ZoomTool,
+    },
 };
 use gtk::{
     cairo::{Context, Format, ImageSurface},
@@ -11,16 +22,29 @@ use gtk::{
     gdk_pixbuf::Pixbuf, glib::variant::ToVariant,
 };
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Tools {
     Pan,
     Brush,
+    Clone,
+    Heal,
+    Zoom,
+    Picker,
+    Assistant,
+    Select,
 }
 
 impl Tools {
     pub fn as_str(&self) -> &'static str {
         match self {
             Tools::Pan => "pan",
-            Tools::Brush => "brush"
+            Tools::Brush => "brush",
+            Tools::Clone => "clone",
+            Tools::Heal => "heal",
+            Tools::Zoom => "zoom",
+            Tools::Picker => "picker",
+            Tools::Assistant => "assistant",
+            Tools::Select => "select",
         }
     }
 }
@@ -38,6 +62,12 @@ impl FromStr for Tools {
         match s {
             "pan" => Ok(Tools::Pan),
             "brush" => Ok(Tools::Brush),
+            "clone" => Ok(Tools::Clone),
+            "heal" => Ok(Tools::Heal),
+            "zoom" => Ok(Tools::Zoom),
+            "picker" => Ok(Tools::Picker),
+            "assistant" => Ok(Tools::Assistant),
+            "select" => Ok(Tools::Select),
             _ => Err("Tool Invalid")
         }
     }
@@ -50,7 +80,18 @@ pub struct App {
     zoom: ZoomTool,
     rotate: RotateTool,
     brush: BrushTool,
-    active_tool: Tools
+    clone_stamp: CloneTool,
+    healing: CloneTool,
+    picker: ColorPickerTool,
+    assistant: AssistantTool,
+    select: SelectTool,
+    foreground: Color,
+    background: Color,
+    active_tool: Tools,
+    // Ferramentas temporarias, cada uma presa a tecla que a ativou.
+    momentary: Vec<(Key, Tools)>,
+    pointer: PointerEvent,
+    dragging: bool,
 }
 
 impl App {
@@ -61,7 +102,17 @@ impl App {
             zoom: ZoomTool::new(),
             rotate: RotateTool::new(),
             brush: BrushTool::new(),
+            clone_stamp: CloneTool::new(CloneMode::Clone),
+            healing: CloneTool::new(CloneMode::Heal),
+            picker: ColorPickerTool::new(),
+            assistant: AssistantTool::new(),
+            select: SelectTool::new(),
+            foreground: Color::BLACK,
+            background: Color::WHITE,
             active_tool: Tools::Pan,
+            momentary: Vec::new(),
+            pointer: PointerEvent::mouse(Point::ZERO),
+            dragging: false,
         }
     }
 
@@ -69,6 +120,146 @@ impl App {
         self.active_tool = tool;
     }
 
+    // A ferramenta temporaria do topo da pilha, ou a escolhida.
+    fn current_tool(&self) -> Tools {
+        self.momentary.last().map_or(self.active_tool, |(_, tool)| *tool)
+    }
+
+    pub fn set_clone_source(&mut self, screen_point: Point) -> bool {
+        if !matches!(self.current_tool(), Tools::Clone | Tools::Heal) {
+            return false;
+        }
+        let source = self.canvas.screen_to_canvas_coords(screen_point);
+        self.clone_stamp.set_source(source);
+        self.healing.set_source(source);
+        true
+    }
+
+    pub fn set_clone_aligned(&mut self, aligned: bool) {
+        self.clone_stamp.set_aligned(aligned);
+        self.healing.set_aligned(aligned);
+    }
+
+    pub fn pressure_curve(&self, index: usize) -> Option<Curve> {
+        self.brush.pressure_curve(index).cloned()
+    }
+
+    pub fn set_pressure_curve(&mut self, index: usize, curve: Curve) {
+        self.brush.set_pressure_curve(index, curve);
+    }
+
+    pub fn foreground(&self) -> Color {
+        self.foreground
+    }
+
+    pub fn set_foreground(&mut self, color: Color) {
+        self.foreground = color;
+        self.brush.set_colors(self.foreground, self.background);
+    }
+
+    pub fn set_background(&mut self, color: Color) {
+        self.background = color;
+        self.brush.set_colors(self.foreground, self.background);
+    }
+
+    pub fn brush_dynamics_params(&self) -> Vec<FilterParam> {
+        self.brush.dynamics_params()
+    }
+
+    pub fn set_brush_dynamics_param(&mut self, key: &str, value: f64) {
+        self.brush.set_dynamics_param(key, value);
+    }
+
+    // Simetria e assistentes sao do documento, mas aparecem junto dos
+    // ajustes do pincel.
+    pub fn brush_params(&self) -> Vec<FilterParam> {
+        let mut params = self.brush.params();
+        params.extend(self.canvas.symmetry().params());
+        params.extend(self.canvas.assistants().params());
+        params
+    }
+
+    pub fn set_brush_param(&mut self, key: &str, value: f64) {
+        if !self.canvas.symmetry_mut().set_param(key, value)
+            && !self.canvas.assistants_mut().set_param(key, value)
+        {
+            self.brush.set_param(key, value);
+        }
+    }
+
+    pub fn brush_blend_modes(&self) -> Vec<&'static str> {
+        BrushTool::blend_modes()
+    }
+
+    pub fn brush_blend_mode(&self) -> usize {
+        self.brush.blend_mode()
+    }
+
+    pub fn set_brush_blend_mode(&mut self, index: usize) {
+        self.brush.set_blend_mode(index);
+    }
+
+    pub fn dual_brush(&self) -> Option<String> {
+        self.brush.dual_brush()
+    }
+
+    pub fn set_dual_brush(&mut self, name: Option<String>) {
+        self.brush.set_dual_brush(name);
+    }
+
+    pub fn load_paper_texture(&mut self, path: &Path) -> Result<()> {
+        self.brush.load_paper_texture(path)
+    }
+
+    pub fn brush_names(&self) -> (Vec<String>, String) {
+        self.brush.brush_names()
+    }
+
+    pub fn brush_thumbnail(&mut self, name: &str) -> Option<ImageSurface> {
+        self.brush.brush_thumbnail(name)
+    }
+
+    pub fn brush_cache_stats(&self) -> String {
+        self.brush.cache_stats().to_string()
+    }
+
+    pub fn select_brush(&mut self, name: &str) {
+        self.brush.select_brush(name);
+    }
+
+    pub fn connect_library_changed(&self, f: impl Fn() + 'static) {
+        self.brush.connect_library_changed(f);
+    }
+
+    pub fn reload_brushes(&mut self) -> bool {
+        self.brush.reload_brushes()
+    }
+
+    pub fn import_brushes(&mut self, path: &Path) -> Result<usize> {
+        self.brush.import_brushes(path)
+    }
+
+    pub fn has_selection(&self) -> bool {
+        self.canvas.selection().is_some()
+    }
+
+    // Pincel a partir da selecao, ou da imagem inteira sem selecao.
+    pub fn define_brush(&mut self, name: &str) -> Result<String> {
+        let (Some(image), Some(region)) = (self.canvas.image(), self.canvas.active_region()) else {
+            bail!("Nenhuma imagem aberta");
+        };
+        let pixels = PixelBuffer::from_rect(image, region)?;
+        self.brush.define_brush(name, &pixels)
+    }
+
+    pub fn save_brush_preset(&mut self, path: &Path) -> Result<()> {
+        self.brush.save_preset(path)
+    }
+
+    pub fn export_brush_presets(&mut self, path: &Path) -> Result<()> {
+        self.brush.export_presets(path)
+    }
+
     pub fn zoom_in(&mut self) {
         self.zoom.zoom_in(&mut self.canvas);
     }
@@ -91,21 +282,124 @@ impl App {
         self.rotate.rotate_right(&mut self.canvas);
     }
 
-    pub fn open(&mut self, pixbuf: Pixbuf) {
+    pub fn open(&mut self, pixbuf: Pixbuf, path: &str) {
         let image = ImageSurface::create(Format::ARgb32, pixbuf.width(), pixbuf.height()).unwrap();
         let ctx = Context::new(&image).unwrap();
         ctx.set_source_pixbuf(&pixbuf, 0., 0.);
         ctx.paint().unwrap();
 
-        self.canvas.open(image);
+        self.canvas.open(image, path);
+    }
+
+    fn filtered_region(&self, filter: &dyn Filter) -> Option<PixelBuffer> {
+        let image = self.canvas.image()?;
+        let region = self.canvas.active_region()?;
+        let mut buffer = PixelBuffer::from_rect(image, region).ok()?;
+        filter.apply(&mut buffer);
+        Some(buffer)
+    }
+
+    pub fn preview_filter(&mut self, filter: &dyn Filter) {
+        let buffer = self.filtered_region(filter);
+        self.canvas.set_preview(buffer.as_ref());
+    }
+
+    pub fn cancel_filter_preview(&mut self) {
+        self.canvas.set_preview(None);
+    }
+
+    pub fn apply_filter(&mut self, filter: &dyn Filter) {
+        self.canvas.set_preview(None);
+        let Some(buffer) = self.filtered_region(filter) else {
+            return;
+        };
+        if let Some(ctx) = self.canvas.get_image_context()
+            && let Err(e) = buffer.paint_on(&ctx)
+        {
+            eprintln!("Erro ao aplicar o filtro {}: {}", filter.name(), e);
+        }
     }
 
     pub fn on_event(&mut self, events: AppEvents, state: &mut ProgramState) {
-        match self.active_tool {
+        self.spring_load(events, state);
+        self.dispatch(events, state);
+        self.zoom.on_event(events, &mut self.canvas, state);
+        if let Some(color) = self.picker.take_picked() {
+            self.set_foreground(color);
+            state.request_color_sync();
+        }
+    }
+
+    fn dispatch(&mut self, events: AppEvents, state: &mut ProgramState) {
+        match self.current_tool() {
             Tools::Pan => self.pan.on_event(events, &mut self.canvas, state),
             Tools::Brush => self.brush.on_event(events, &mut self.canvas, state),
+            Tools::Clone => self.clone_stamp.on_event(events, &mut self.canvas, state),
+            Tools::Heal => self.healing.on_event(events, &mut self.canvas, state),
+            Tools::Zoom => self.zoom.on_click(events, &mut self.canvas, state),
+            Tools::Picker => self.picker.on_event(events, &mut self.canvas, state),
+            Tools::Assistant => self.assistant.on_event(events, &mut self.canvas, state),
+            Tools::Select => self.select.on_event(events, &mut self.canvas, state),
+        }
+    }
+
+    // Espaco = mover, Ctrl+Espaco = zoom, Alt no pincel = conta-gotas;
+    // soltar a tecla volta a ferramenta anterior.
+    fn spring_load(&mut self, events: AppEvents, state: &mut ProgramState) {
+        match events {
+            AppEvents::KeyDown(KeyEvent { key, modifiers }) => {
+                // Repeticao da tecla pressionada.
+                if self.momentary.iter().any(|(held, _)| *held == key) {
+                    state.consume_key();
+                    return;
+                }
+                // Com outros modificadores (Alt+Tab, Shift+Espaco) a tecla
+                // segue para a janela.
+                let others = modifiers.shift || modifiers.super_key;
+                let tool = match key {
+                    _ if others => return,
+                    Key::Space if modifiers.alt => return,
+                    Key::Space if modifiers.ctrl => Tools::Zoom,
+                    Key::Space => Tools::Pan,
+                    Key::Alt if modifiers.ctrl => return,
+                    Key::Alt if self.current_tool() == Tools::Brush => Tools::Picker,
+                    _ => return,
+                };
+                self.end_drag(modifiers, state);
+                self.momentary.push((key, tool));
+                state.consume_key();
+            }
+            AppEvents::KeyUp(KeyEvent { key, modifiers }) => {
+                if let Some(index) = self.momentary.iter().position(|(held, _)| *held == key) {
+                    self.end_drag(modifiers, state);
+                    self.momentary.remove(index);
+                }
+            }
+            AppEvents::MouseDown(event) => {
+                self.pointer = event;
+                self.dragging = true;
+            }
+            AppEvents::MouseMove(event) => self.pointer = event,
+            AppEvents::MouseUp(_) => self.dragging = false,
+            _ => {}
+        }
+    }
+
+    // A janela perdeu o foco: o KeyUp das teclas seguras pode nao chegar.
+    pub fn release_keys(&mut self, state: &mut ProgramState) {
+        if !self.momentary.is_empty() {
+            self.end_drag(Modifiers::default(), state);
+            self.momentary.clear();
+        }
+    }
+
+    // Trocar de ferramenta no meio de um arrasto encerra o arrasto na anterior.
+    fn end_drag(&mut self, modifiers: Modifiers, state: &mut ProgramState) {
+        if self.dragging {
+            let event = self.pointer.with_modifiers(modifiers);
+            self.dispatch(AppEvents::MouseUp(event), state);
+            self.dragging = false;
         }
-        self.zoom.on_event(events, &mut self.canvas, state);
     }
 
     pub fn draw(&mut self, ctx: &Context) {
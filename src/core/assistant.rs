@@ -0,0 +1,351 @@
+use crate::{filters::FilterParam, geometry::Point};
+use gtk::cairo::Context;
+use std::f64::consts::TAU;
+
+// Deslocamento minimo antes de escolher a direcao entre varias guias.
+const DIRECTION_THRESHOLD: f64 = 4.0;
+// Raio dos pontos de controle na tela, em pixels.
+pub const HANDLE_RADIUS: f64 = 5.0;
+const PERSPECTIVE_RAYS: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AssistantMode {
+    Off,
+    Ruler,
+    Parallel,
+    OnePoint,
+    TwoPoint,
+    ThreePoint,
+    Ellipse,
+}
+
+impl AssistantMode {
+    const ALL: [AssistantMode; 7] = [
+        AssistantMode::Off,
+        AssistantMode::Ruler,
+        AssistantMode::Parallel,
+        AssistantMode::OnePoint,
+        AssistantMode::TwoPoint,
+        AssistantMode::ThreePoint,
+        AssistantMode::Ellipse,
+    ];
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|mode| mode == self).unwrap_or(0)
+    }
+
+    // Pontos de controle iniciais, relativos ao tamanho da imagem.
+    fn default_handles(&self) -> Vec<Point> {
+        let points: &[(f64, f64)] = match self {
+            AssistantMode::Off => &[],
+            AssistantMode::Ruler | AssistantMode::Parallel => &[(0.25, 0.5), (0.75, 0.5)],
+            AssistantMode::OnePoint => &[(0.5, 0.4)],
+            AssistantMode::TwoPoint => &[(-0.2, 0.4), (1.2, 0.4)],
+            AssistantMode::ThreePoint => &[(-0.2, 0.4), (1.2, 0.4), (0.5, 1.8)],
+            // Centro, fim do eixo maior e fim do eixo menor.
+            AssistantMode::Ellipse => &[(0.5, 0.5), (0.8, 0.5), (0.5, 0.65)],
+        };
+        points.iter().map(|&(x, y)| Point::new(x, y)).collect()
+    }
+}
+
+// Guia em que um traco e preso.
+#[derive(Debug, Clone, Copy)]
+enum Guide {
+    Line {
+        origin: Point,
+        dx: f64,
+        dy: f64,
+    },
+    Ellipse {
+        center: Point,
+        angle: f64,
+        a: f64,
+        b: f64,
+    },
+}
+
+impl Guide {
+    fn line(origin: Point, toward: Point) -> Option<Guide> {
+        let (dx, dy) = (toward.x - origin.x, toward.y - origin.y);
+        let length = dx.hypot(dy);
+        (length > f64::EPSILON).then(|| Guide::Line {
+            origin,
+            dx: dx / length,
+            dy: dy / length,
+        })
+    }
+
+    // Quanto a guia acompanha o movimento inicial (dx, dy unitario).
+    fn alignment(&self, dx: f64, dy: f64) -> f64 {
+        match self {
+            Guide::Line { dx: gx, dy: gy, .. } => (gx * dx + gy * dy).abs(),
+            Guide::Ellipse { .. } => 1.0,
+        }
+    }
+
+    fn project(&self, p: Point) -> Point {
+        match *self {
+            Guide::Line { origin, dx, dy } => {
+                let t = (p.x - origin.x) * dx + (p.y - origin.y) * dy;
+                Point::new(origin.x + dx * t, origin.y + dy * t)
+            }
+            Guide::Ellipse {
+                center,
+                angle,
+                a,
+                b,
+            } => {
+                let (sin, cos) = angle.sin_cos();
+                let (x, y) = (p.x - center.x, p.y - center.y);
+                let u = (x * cos + y * sin) / a;
+                let v = (-x * sin + y * cos) / b;
+                let n = u.hypot(v);
+                let (u, v) = if n > f64::EPSILON {
+                    (u / n, v / n)
+                } else {
+                    (1.0, 0.0)
+                };
+                let (lx, ly) = (u * a, v * b);
+                Point::new(
+                    center.x + lx * cos - ly * sin,
+                    center.y + lx * sin + ly * cos,
+                )
+            }
+        }
+    }
+}
+
+// Traco preso a uma guia; com varias, a direcao inicial escolhe uma.
+pub struct AssistedStroke {
+    start: Point,
+    guides: Vec<Guide>,
+    chosen: Option<Guide>,
+}
+
+impl AssistedStroke {
+    pub fn project(&mut self, point: Point) -> Point {
+        if self.chosen.is_none() {
+            let (dx, dy) = (point.x - self.start.x, point.y - self.start.y);
+            let distance = dx.hypot(dy);
+            if self.guides.len() > 1 && distance < DIRECTION_THRESHOLD {
+                return self.start;
+            }
+            let (dx, dy) = (
+                dx / distance.max(f64::EPSILON),
+                dy / distance.max(f64::EPSILON),
+            );
+            self.chosen = self
+                .guides
+                .iter()
+                .max_by(|g1, g2| g1.alignment(dx, dy).total_cmp(&g2.alignment(dx, dy)))
+                .copied();
+        }
+        self.chosen.map_or(point, |guide| guide.project(point))
+    }
+}
+
+// Assistentes de desenho do documento; os pontos de controle sao
+// relativos ao tamanho da imagem, e podem ficar fora dela.
+#[derive(Debug, Clone)]
+pub struct Assistants {
+    pub mode: AssistantMode,
+    handles: Vec<Point>,
+}
+
+impl Default for Assistants {
+    fn default() -> Self {
+        Assistants {
+            mode: AssistantMode::Off,
+            handles: Vec::new(),
+        }
+    }
+}
+
+impl Assistants {
+    pub fn params(&self) -> Vec<FilterParam> {
+        vec![
+            FilterParam::choice(
+                "assistant",
+                "Mode",
+                &[
+                    "Off",
+                    "Ruler",
+                    "Parallel",
+                    "1-point perspective",
+                    "2-point perspective",
+                    "3-point perspective",
+                    "Ellipse",
+                ],
+                self.mode.index(),
+            )
+            .in_group("Assistant"),
+        ]
+    }
+
+    // Retorna false para chaves que nao sao dos assistentes.
+    pub fn set_param(&mut self, key: &str, value: f64) -> bool {
+        if key != "assistant" {
+            return false;
+        }
+        let index = (value.round().max(0.0) as usize).min(AssistantMode::ALL.len() - 1);
+        let mode = AssistantMode::ALL[index];
+        if mode != self.mode {
+            self.mode = mode;
+            self.handles = mode.default_handles();
+        }
+        true
+    }
+
+    pub fn handles(&self, width: f64, height: f64) -> Vec<Point> {
+        self.handles
+            .iter()
+            .map(|h| Point::new(h.x * width, h.y * height))
+            .collect()
+    }
+
+    pub fn set_handle(&mut self, index: usize, point: Point, width: f64, height: f64) {
+        if let Some(handle) = self.handles.get_mut(index) {
+            *handle = Point::new(point.x / width, point.y / height);
+        }
+    }
+
+    pub fn handle_at(&self, point: Point, radius: f64, width: f64, height: f64) -> Option<usize> {
+        self.handles(width, height)
+            .iter()
+            .position(|h| (h.x - point.x).hypot(h.y - point.y) <= radius)
+    }
+
+    pub fn begin_stroke(&self, start: Point, width: f64, height: f64) -> Option<AssistedStroke> {
+        let h = self.handles(width, height);
+        let guides = match self.mode {
+            AssistantMode::Off => return None,
+            AssistantMode::Ruler => Guide::line(h[0], h[1]).into_iter().collect(),
+            AssistantMode::Parallel => {
+                let toward = Point::new(start.x + h[1].x - h[0].x, start.y + h[1].y - h[0].y);
+                Guide::line(start, toward).into_iter().collect()
+            }
+            AssistantMode::OnePoint | AssistantMode::TwoPoint | AssistantMode::ThreePoint => {
+                let mut guides: Vec<Guide> =
+                    h.iter().filter_map(|vp| Guide::line(start, *vp)).collect();
+                // Linhas sem ponto de fuga: verticais (1 e 2 pontos) e horizontais (1 ponto).
+                if self.mode != AssistantMode::ThreePoint {
+                    guides.extend(Guide::line(start, Point::new(start.x, start.y + 1.0)));
+                }
+                if self.mode == AssistantMode::OnePoint {
+                    guides.extend(Guide::line(start, Point::new(start.x + 1.0, start.y)));
+                }
+                guides
+            }
+            AssistantMode::Ellipse => Self::ellipse(&h)
+                .and_then(|guide| Self::concentric(guide, start))
+                .into_iter()
+                .collect(),
+        };
+        Some(AssistedStroke {
+            start,
+            guides,
+            chosen: None,
+        })
+    }
+
+    fn ellipse(h: &[Point]) -> Option<Guide> {
+        let (center, major, minor) = (h[0], h[1], h[2]);
+        let angle = (major.y - center.y).atan2(major.x - center.x);
+        let a = (major.x - center.x).hypot(major.y - center.y);
+        let (sin, cos) = angle.sin_cos();
+        let b = (-(minor.x - center.x) * sin + (minor.y - center.y) * cos).abs();
+        (a > f64::EPSILON && b > f64::EPSILON).then_some(Guide::Ellipse {
+            center,
+            angle,
+            a,
+            b,
+        })
+    }
+
+    // Elipse concentrica a guia que passa pelo inicio do traco.
+    fn concentric(guide: Guide, start: Point) -> Option<Guide> {
+        let Guide::Ellipse {
+            center,
+            angle,
+            a,
+            b,
+        } = guide
+        else {
+            return None;
+        };
+        let (sin, cos) = angle.sin_cos();
+        let (x, y) = (start.x - center.x, start.y - center.y);
+        let scale = ((x * cos + y * sin) / a).hypot((-x * sin + y * cos) / b);
+        (scale > f64::EPSILON).then_some(Guide::Ellipse {
+            center,
+            angle,
+            a: a * scale,
+            b: b * scale,
+        })
+    }
+
+    // Desenha as guias; `ctx` ja esta em coordenadas da imagem.
+    pub fn draw(&self, ctx: &Context, width: f64, height: f64, zoom: f64) {
+        if self.mode == AssistantMode::Off {
+            return;
+        }
+        let h = self.handles(width, height);
+        let reach = width.hypot(height) * 4.0;
+        ctx.save().unwrap();
+        ctx.set_source_rgba(1.0, 0.4, 0.0, 0.8);
+        ctx.set_line_width(1.0 / zoom);
+
+        ctx.save().unwrap();
+        ctx.rectangle(0.0, 0.0, width, height);
+        ctx.clip();
+        match self.mode {
+            AssistantMode::Ruler | AssistantMode::Parallel => {
+                if let Some(Guide::Line { origin, dx, dy }) = Guide::line(h[0], h[1]) {
+                    ctx.move_to(origin.x - dx * reach, origin.y - dy * reach);
+                    ctx.line_to(origin.x + dx * reach, origin.y + dy * reach);
+                }
+            }
+            AssistantMode::OnePoint | AssistantMode::TwoPoint | AssistantMode::ThreePoint => {
+                for vp in &h {
+                    for i in 0..PERSPECTIVE_RAYS {
+                        let angle = TAU * i as f64 / PERSPECTIVE_RAYS as f64;
+                        ctx.move_to(vp.x, vp.y);
+                        ctx.line_to(vp.x + angle.cos() * reach, vp.y + angle.sin() * reach);
+                    }
+                }
+                // Horizonte entre os dois primeiros pontos de fuga.
+                if h.len() >= 2 {
+                    ctx.move_to(h[0].x, h[0].y);
+                    ctx.line_to(h[1].x, h[1].y);
+                }
+            }
+            AssistantMode::Ellipse => {
+                if let Some(Guide::Ellipse {
+                    center,
+                    angle,
+                    a,
+                    b,
+                }) = Self::ellipse(&h)
+                {
+                    ctx.save().unwrap();
+                    ctx.translate(center.x, center.y);
+                    ctx.rotate(angle);
+                    ctx.scale(a, b);
+                    ctx.arc(0.0, 0.0, 1.0, 0.0, TAU);
+                    ctx.restore().unwrap();
+                }
+            }
+            AssistantMode::Off => {}
+        }
+        ctx.stroke().unwrap();
+        ctx.restore().unwrap();
+
+        for handle in &h {
+            ctx.new_sub_path();
+            ctx.arc(handle.x, handle.y, HANDLE_RADIUS / zoom, 0.0, TAU);
+        }
+        ctx.stroke().unwrap();
+        ctx.restore().unwrap();
+    }
+}
@@ -1,8 +1,79 @@
-use crate::geometry::Point;
-use gtk::cairo::{Context, ImageSurface, Matrix};
+use crate::{
+    core::{
+        assistant::{Assistants, AssistedStroke, HANDLE_RADIUS},
+        pixel_buffer::PixelBuffer,
+        symmetry::Symmetry,
+    },
+    geometry::{Point, Rect},
+};
+use anyhow::Result;
+use gtk::cairo::{Context, FillRule, Format, ImageSurface, Matrix, Operator};
+use std::collections::HashMap;
+
+// Como o traco entra na imagem: operador do Cairo ou funcao de mistura
+// sobre cores RGB diretas (fonte, destino).
+#[derive(Clone, Copy)]
+pub enum StrokeBlend {
+    Operator(Operator),
+    Custom(fn([f32; 3], [f32; 3]) -> [f32; 3]),
+}
+
+// Traco em andamento: os carimbos acumulam aqui e so entram na imagem
+// no fim do traco, limitados pela opacidade.
+pub struct StrokeOverlay {
+    surface: ImageSurface,
+    opacity: f64,
+    blend: StrokeBlend,
+    // Resultado da mistura customizada ja calculado para o preview.
+    blended: Option<PixelBuffer>,
+    // Area ja pintada pelos carimbos, informada por quem pinta.
+    dirty: Option<Rect>,
+}
+
+impl StrokeOverlay {
+    // Pixels da imagem com o traco misturado por `blend`, so onde ha tinta.
+    fn blend_custom(
+        &self,
+        image: &ImageSurface,
+        blend: fn([f32; 3], [f32; 3]) -> [f32; 3],
+    ) -> Result<Option<PixelBuffer>> {
+        let bounds = Rect::new(0, 0, self.surface.width(), self.surface.height());
+        let Some(rect) = self.dirty.and_then(|dirty| dirty.intersect(&bounds)) else {
+            return Ok(None);
+        };
+        let mut dst = PixelBuffer::from_rect(image, rect)?;
+        let src = PixelBuffer::from_rect(&self.surface, rect)?;
+        for y in rect.y..rect.y + rect.height {
+            for x in rect.x..rect.x + rect.width {
+                let s = src.get(x, y);
+                let a = s[3] * self.opacity as f32;
+                if a <= 0.0 {
+                    continue;
+                }
+                let d = dst.get(x, y);
+                let mixed = blend([s[0], s[1], s[2]], [d[0], d[1], d[2]]);
+                // Sobre area transparente a mistura vira a propria fonte.
+                let out = [0, 1, 2].map(|c| {
+                    let m = mixed[c] * d[3] + s[c] * (1.0 - d[3]);
+                    d[c] + (m - d[c]) * a
+                });
+                dst.set(x, y, [out[0], out[1], out[2], d[3] + a * (1.0 - d[3])]);
+            }
+        }
+        Ok(Some(dst))
+    }
+}
 
 pub struct Canvas {
     image: Option<ImageSurface>,
+    selection: Option<Rect>,
+    preview: Option<(ImageSurface, Rect)>,
+    stroke: Option<StrokeOverlay>,
+    // Fazem parte do documento: guardados por caminho e restaurados ao reabrir.
+    symmetry: Symmetry,
+    assistants: Assistants,
+    document: Option<String>,
+    guides: HashMap<String, (Symmetry, Assistants)>,
     pub position: Point,
     pub zoom: f64,
     pub rotation: f64,
@@ -13,6 +84,13 @@ impl Canvas {
     pub fn new() -> Self {
         Canvas {
             image: None,
+            selection: None,
+            preview: None,
+            stroke: None,
+            symmetry: Symmetry::default(),
+            assistants: Assistants::default(),
+            document: None,
+            guides: HashMap::new(),
             position: Point::ZERO,
             zoom: 1.0,
             rotation: 0.0,
@@ -20,8 +98,161 @@ impl Canvas {
         }
     }
 
-    pub fn open(&mut self, image: ImageSurface) {
+    pub fn open(&mut self, image: ImageSurface, document: &str) {
         self.image = Some(image);
+        self.selection = None;
+        self.preview = None;
+        self.stroke = None;
+        if let Some(previous) = self.document.replace(document.to_string()) {
+            let guides = (self.symmetry.clone(), self.assistants.clone());
+            self.guides.insert(previous, guides);
+        }
+        let (symmetry, assistants) = self.guides.remove(document).unwrap_or_default();
+        self.symmetry = symmetry;
+        self.assistants = assistants;
+    }
+
+    pub fn symmetry(&self) -> &Symmetry {
+        &self.symmetry
+    }
+
+    pub fn symmetry_mut(&mut self) -> &mut Symmetry {
+        &mut self.symmetry
+    }
+
+    pub fn assistants(&self) -> &Assistants {
+        &self.assistants
+    }
+
+    pub fn assistants_mut(&mut self) -> &mut Assistants {
+        &mut self.assistants
+    }
+
+    fn image_size(&self) -> Option<(f64, f64)> {
+        let image = self.image.as_ref()?;
+        Some((image.width() as f64, image.height() as f64))
+    }
+
+    // Guia do assistente para um traco que comeca em `start`.
+    pub fn assisted_stroke(&self, start: Point) -> Option<AssistedStroke> {
+        let (width, height) = self.image_size()?;
+        self.assistants.begin_stroke(start, width, height)
+    }
+
+    pub fn assistant_handle_at(&self, point: Point) -> Option<usize> {
+        let (width, height) = self.image_size()?;
+        let radius = HANDLE_RADIUS * 2.0 / self.zoom;
+        self.assistants.handle_at(point, radius, width, height)
+    }
+
+    pub fn move_assistant_handle(&mut self, index: usize, point: Point) {
+        if let Some((width, height)) = self.image_size() {
+            self.assistants.set_handle(index, point, width, height);
+        }
+    }
+
+    // Matrizes das copias espelhadas de cada carimbo.
+    pub fn symmetry_transforms(&self) -> Vec<Matrix> {
+        match self.image.as_ref() {
+            Some(image) => self
+                .symmetry
+                .transforms(image.width() as f64, image.height() as f64),
+            None => vec![Matrix::identity()],
+        }
+    }
+
+    // Recortada pela imagem; vazia vira "sem selecao".
+    pub fn set_selection(&mut self, selection: Option<Rect>) {
+        self.selection = match (selection, self.image.as_ref()) {
+            (Some(rect), Some(image)) => {
+                rect.intersect(&Rect::new(0, 0, image.width(), image.height()))
+            }
+            _ => None,
+        };
+    }
+
+    pub fn selection(&self) -> Option<Rect> {
+        self.selection
+    }
+
+    // Regiao afetada por filtros: a selecao, ou a imagem inteira.
+    pub fn active_region(&self) -> Option<Rect> {
+        let image = self.image.as_ref()?;
+        let bounds = Rect::new(0, 0, image.width(), image.height());
+        match self.selection {
+            Some(selection) => selection.intersect(&bounds),
+            None => Some(bounds),
+        }
+    }
+
+    pub fn set_preview(&mut self, preview: Option<&PixelBuffer>) {
+        self.preview = preview.and_then(|buffer| {
+            let surface = buffer.to_surface().ok()?;
+            Some((surface, buffer.rect()))
+        });
+    }
+
+    pub fn begin_stroke(&mut self, opacity: f64, blend: StrokeBlend) -> Result<()> {
+        let Some(image) = self.image.as_ref() else {
+            return Ok(());
+        };
+        let surface = ImageSurface::create(Format::ARgb32, image.width(), image.height())?;
+        self.stroke = Some(StrokeOverlay {
+            surface,
+            opacity: opacity.clamp(0.0, 1.0),
+            blend,
+            blended: None,
+            dirty: None,
+        });
+        Ok(())
+    }
+
+    // Quem pede o contexto vai pintar: o preview da mistura fica velho.
+    pub fn stroke_context(&mut self) -> Option<Context> {
+        let stroke = self.stroke.as_mut()?;
+        stroke.blended = None;
+        Context::new(stroke.surface.clone()).ok()
+    }
+
+    // Soma `rect` a area pintada do traco.
+    pub fn mark_stroke(&mut self, rect: Rect) {
+        if let Some(stroke) = self.stroke.as_mut() {
+            stroke.blended = None;
+            stroke.dirty = Some(match stroke.dirty {
+                Some(dirty) => dirty.union(&rect),
+                None => rect,
+            });
+        }
+    }
+
+    pub fn commit_stroke(&mut self) -> Result<()> {
+        let (Some(stroke), Some(ctx)) = (self.stroke.take(), self.get_image_context()) else {
+            return Ok(());
+        };
+        match stroke.blend {
+            StrokeBlend::Operator(operator) => {
+                ctx.set_operator(operator);
+                ctx.set_source_surface(&stroke.surface, 0., 0.)?;
+                ctx.paint_with_alpha(stroke.opacity)?;
+            }
+            StrokeBlend::Custom(blend) => {
+                let Some(image) = self.image.as_ref() else {
+                    return Ok(());
+                };
+                let blended = match stroke.blended {
+                    Some(blended) => Some(blended),
+                    None => stroke.blend_custom(image, blend)?,
+                };
+                if let Some(blended) = blended {
+                    blended.paint_on(&ctx)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn image(&self) -> Option<&ImageSurface> {
+        self.image.as_ref()
     }
 
     pub fn get_image_context(&self) -> Option<Context> {
@@ -50,9 +281,95 @@ impl Canvas {
 
             self.matrix = ctx.matrix();
 
+            if let Some((_, rect)) = self.preview.as_ref() {
+                ctx.rectangle(0., 0., image.width() as f64, image.height() as f64);
+                ctx.rectangle(rect.x as f64, rect.y as f64, rect.width as f64, rect.height as f64);
+                ctx.set_fill_rule(FillRule::EvenOdd);
+                ctx.clip();
+            }
+
+            if self.stroke.is_some() {
+                ctx.push_group();
+            }
             ctx.set_source_surface(image, 0., 0.).unwrap();
             ctx.paint().unwrap();
+            ctx.reset_clip();
+
+            if let Some((preview, rect)) = self.preview.as_ref() {
+                ctx.set_source_surface(preview, rect.x as f64, rect.y as f64).unwrap();
+                ctx.paint().unwrap();
+            }
+
+            if let Some(stroke) = self.stroke.as_mut() {
+                match stroke.blend {
+                    StrokeBlend::Operator(operator) => {
+                        ctx.set_operator(operator);
+                        ctx.set_source_surface(&stroke.surface, 0., 0.).unwrap();
+                        ctx.paint_with_alpha(stroke.opacity).unwrap();
+                    }
+                    // Mesma mistura do commit, calculada uma vez por mudanca no traco.
+                    StrokeBlend::Custom(blend) => {
+                        if stroke.blended.is_none() {
+                            match stroke.blend_custom(image, blend) {
+                                Ok(blended) => stroke.blended = blended,
+                                Err(e) => eprintln!("Erro ao misturar o traco: {}", e),
+                            }
+                        }
+                        if let Some(blended) = stroke.blended.as_ref() {
+                            blended.paint_on(ctx).unwrap();
+                        }
+                    }
+                }
+                ctx.pop_group_to_source().unwrap();
+                ctx.set_operator(Operator::Over);
+                ctx.paint().unwrap();
+            }
+            self.symmetry
+                .draw_guide(ctx, image.width() as f64, image.height() as f64, self.zoom);
+            self.assistants
+                .draw(ctx, image.width() as f64, image.height() as f64, self.zoom);
+            if let Some(selection) = self.selection {
+                Self::draw_selection(ctx, selection, self.zoom);
+            }
             ctx.restore().unwrap();
         }
     }
+
+    // Contorno tracejado preto e branco, visivel sobre qualquer cor.
+    fn draw_selection(ctx: &Context, rect: Rect, zoom: f64) {
+        let width = 1.0 / zoom;
+        ctx.save().unwrap();
+        ctx.rectangle(
+            rect.x as f64,
+            rect.y as f64,
+            rect.width as f64,
+            rect.height as f64,
+        );
+        ctx.set_line_width(width);
+        ctx.set_source_rgb(1.0, 1.0, 1.0);
+        ctx.stroke_preserve().unwrap();
+        ctx.set_dash(&[4.0 * width, 4.0 * width], 0.0);
+        ctx.set_source_rgb(0.0, 0.0, 0.0);
+        ctx.stroke().unwrap();
+        ctx.restore().unwrap();
+    }
+}
+
+// Pixels do dispositivo cobertos por um retangulo no espaco atual de `ctx`.
+pub fn device_rect(ctx: &Context, x: f64, y: f64, width: f64, height: f64) -> Rect {
+    let (mut x0, mut y0) = (f64::INFINITY, f64::INFINITY);
+    let (mut x1, mut y1) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    let (right, bottom) = (x + width, y + height);
+    for (cx, cy) in [(x, y), (right, y), (x, bottom), (right, bottom)] {
+        let (dx, dy) = ctx.user_to_device(cx, cy);
+        (x0, y0) = (x0.min(dx), y0.min(dy));
+        (x1, y1) = (x1.max(dx), y1.max(dy));
+    }
+    let (x0, y0) = (x0.floor(), y0.floor());
+    Rect::new(
+        x0 as i32,
+        y0 as i32,
+        (x1.ceil() - x0) as i32,
+        (y1.ceil() - y0) as i32,
+    )
 }
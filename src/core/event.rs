@@ -1,9 +1,88 @@
 use crate::geometry::Point;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeviceTool {
+    Mouse,
+    Pen,
+    Eraser,
+}
+
+// Teclas modificadoras pressionadas no momento do evento.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub super_key: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PointerEvent {
+    pub point: Point,
+    pub pressure: f64,
+    pub tilt_x: f64,
+    pub tilt_y: f64,
+    pub device: DeviceTool,
+    pub modifiers: Modifiers,
+}
+
+impl PointerEvent {
+    pub fn mouse(point: Point) -> Self {
+        PointerEvent {
+            point,
+            pressure: 1.0,
+            tilt_x: 0.0,
+            tilt_y: 0.0,
+            device: DeviceTool::Mouse,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    pub fn stylus(point: Point, pressure: f64, tilt_x: f64, tilt_y: f64, device: DeviceTool) -> Self {
+        PointerEvent {
+            point,
+            pressure: pressure.clamp(0.0, 1.0),
+            tilt_x,
+            tilt_y,
+            device,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    pub fn with_modifiers(self, modifiers: Modifiers) -> Self {
+        PointerEvent { modifiers, ..self }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Key {
+    Space,
+    Shift,
+    Ctrl,
+    Alt,
+    Super,
+    Escape,
+    Enter,
+    // Tecla com caractere, em minuscula.
+    Char(char),
+    // Demais teclas pelo codigo (keyval) do GDK.
+    Other(u32),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct KeyEvent {
+    pub key: Key,
+    // Estado ja com o efeito da propria tecla (Shift solto => shift false).
+    pub modifiers: Modifiers,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum AppEvents {
-    MouseUp(Point),
-    MouseMove(Point),
-    MouseDown(Point),
-    ScroolEvent{delta_y: f64}
+    MouseUp(PointerEvent),
+    MouseMove(PointerEvent),
+    MouseDown(PointerEvent),
+    ScroolEvent{delta_y: f64},
+    Tick,
+    KeyDown(KeyEvent),
+    KeyUp(KeyEvent),
 }
\ No newline at end of file
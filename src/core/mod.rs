@@ -1,3 +1,7 @@
 pub mod app;
+pub mod assistant;
 pub mod canvas;
-pub mod event;
\ No newline at end of file
+pub mod color;
+pub mod event;
+pub mod pixel_buffer;
+pub mod symmetry;
\ No newline at end of file
@@ -0,0 +1,168 @@
+use crate::geometry::Rect;
+use anyhow::Result;
+use gtk::cairo::{Context, Format, ImageSurface, Operator};
+
+// Copia de uma regiao de uma ImageSurface ARgb32 (BGRA pre-multiplicado na memoria).
+#[derive(Clone)]
+pub struct PixelBuffer {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    data: Vec<u8>,
+}
+
+impl PixelBuffer {
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        let width = width.max(0);
+        let height = height.max(0);
+        PixelBuffer {
+            x,
+            y,
+            width,
+            height,
+            data: vec![0u8; (width * height * 4) as usize],
+        }
+    }
+
+    pub fn from_surface(surface: &ImageSurface, x: i32, y: i32, width: i32, height: i32) -> Result<Self> {
+        let mut buffer = PixelBuffer::new(x, y, width, height);
+        let surface_width = surface.width();
+        let surface_height = surface.height();
+        let stride = surface.stride() as usize;
+
+        let x0 = x.max(0);
+        let y0 = y.max(0);
+        let x1 = (x + buffer.width).min(surface_width);
+        let y1 = (y + buffer.height).min(surface_height);
+
+        surface.with_data(|src| {
+            for sy in y0..y1 {
+                let src_row = sy as usize * stride;
+                let dst_row = ((sy - y) * buffer.width) as usize * 4;
+                for sx in x0..x1 {
+                    let s = src_row + sx as usize * 4;
+                    let d = dst_row + (sx - x) as usize * 4;
+                    buffer.data[d..d + 4].copy_from_slice(&src[s..s + 4]);
+                }
+            }
+        })?;
+
+        Ok(buffer)
+    }
+
+    pub fn from_image(surface: &ImageSurface) -> Result<Self> {
+        Self::from_surface(surface, 0, 0, surface.width(), surface.height())
+    }
+
+    pub fn from_rect(surface: &ImageSurface, rect: Rect) -> Result<Self> {
+        Self::from_surface(surface, rect.x, rect.y, rect.width, rect.height)
+    }
+
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.x, self.y, self.width, self.height)
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && y >= self.y && x < self.x + self.width && y < self.y + self.height
+    }
+
+    fn index(&self, x: i32, y: i32) -> usize {
+        (((y - self.y) * self.width + (x - self.x)) * 4) as usize
+    }
+
+    // Cor pre-multiplicada em RGBA 0..1, coordenadas absolutas da imagem.
+    pub fn get_premultiplied(&self, x: i32, y: i32) -> [f32; 4] {
+        if !self.contains(x, y) {
+            return [0.0; 4];
+        }
+        let i = self.index(x, y);
+        let px = &self.data[i..i + 4];
+        [
+            px[2] as f32 / 255.0,
+            px[1] as f32 / 255.0,
+            px[0] as f32 / 255.0,
+            px[3] as f32 / 255.0,
+        ]
+    }
+
+    pub fn set_premultiplied(&mut self, x: i32, y: i32, rgba: [f32; 4]) {
+        if !self.contains(x, y) {
+            return;
+        }
+        let a = rgba[3].clamp(0.0, 1.0);
+        let to_u8 = |v: f32| (v.clamp(0.0, a) * 255.0).round() as u8;
+        let i = self.index(x, y);
+        self.data[i] = to_u8(rgba[2]);
+        self.data[i + 1] = to_u8(rgba[1]);
+        self.data[i + 2] = to_u8(rgba[0]);
+        self.data[i + 3] = (a * 255.0).round() as u8;
+    }
+
+    // Cor "straight" (nao pre-multiplicada) em RGBA 0..1.
+    pub fn get(&self, x: i32, y: i32) -> [f32; 4] {
+        let [r, g, b, a] = self.get_premultiplied(x, y);
+        if a <= 0.0 {
+            return [0.0; 4];
+        }
+        [r / a, g / a, b / a, a]
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, rgba: [f32; 4]) {
+        let a = rgba[3].clamp(0.0, 1.0);
+        self.set_premultiplied(x, y, [rgba[0] * a, rgba[1] * a, rgba[2] * a, a]);
+    }
+
+    // Todos os pixels pre-multiplicados, linha a linha.
+    pub fn to_rgba_f32(&self) -> Vec<[f32; 4]> {
+        self.data
+            .chunks_exact(4)
+            .map(|px| {
+                [
+                    px[2] as f32 / 255.0,
+                    px[1] as f32 / 255.0,
+                    px[0] as f32 / 255.0,
+                    px[3] as f32 / 255.0,
+                ]
+            })
+            .collect()
+    }
+
+    pub fn write_rgba_f32(&mut self, pixels: &[[f32; 4]]) {
+        for (i, rgba) in pixels.iter().enumerate().take((self.width * self.height) as usize) {
+            let x = self.x + i as i32 % self.width;
+            let y = self.y + i as i32 / self.width;
+            self.set_premultiplied(x, y, *rgba);
+        }
+    }
+
+    pub fn to_surface(&self) -> Result<ImageSurface> {
+        let stride = Format::ARgb32.stride_for_width(self.width as u32)?;
+        let surface = ImageSurface::create_for_data(
+            self.data.clone(),
+            Format::ARgb32,
+            self.width,
+            self.height,
+            stride,
+        )?;
+        Ok(surface)
+    }
+
+    // Substitui os pixels da regiao no destino (sem blending).
+    pub fn paint_on(&self, ctx: &Context) -> Result<()> {
+        let surface = self.to_surface()?;
+        ctx.save()?;
+        ctx.set_operator(Operator::Source);
+        ctx.rectangle(
+            self.x as f64,
+            self.y as f64,
+            self.width as f64,
+            self.height as f64,
+        );
+        ctx.clip();
+        ctx.set_source_surface(&surface, self.x as f64, self.y as f64)?;
+        ctx.paint()?;
+        ctx.restore()?;
+        Ok(())
+    }
+}
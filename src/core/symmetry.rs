@@ -0,0 +1,159 @@
+use crate::filters::FilterParam;
+use gtk::cairo::{Context, Matrix};
+use std::f64::consts::TAU;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymmetryMode {
+    Off,
+    // Espelho no eixo vertical (esquerda/direita).
+    Vertical,
+    // Espelho no eixo horizontal (cima/baixo).
+    Horizontal,
+    Both,
+    Radial,
+}
+
+impl SymmetryMode {
+    const ALL: [SymmetryMode; 5] = [
+        SymmetryMode::Off,
+        SymmetryMode::Vertical,
+        SymmetryMode::Horizontal,
+        SymmetryMode::Both,
+        SymmetryMode::Radial,
+    ];
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|mode| mode == self).unwrap_or(0)
+    }
+}
+
+// Simetria do documento; o centro e relativo ao tamanho da imagem.
+#[derive(Debug, Clone)]
+pub struct Symmetry {
+    pub mode: SymmetryMode,
+    pub segments: u32,
+    pub center_x: f64,
+    pub center_y: f64,
+}
+
+impl Default for Symmetry {
+    fn default() -> Self {
+        Symmetry {
+            mode: SymmetryMode::Off,
+            segments: 6,
+            center_x: 0.5,
+            center_y: 0.5,
+        }
+    }
+}
+
+impl Symmetry {
+    pub fn params(&self) -> Vec<FilterParam> {
+        vec![
+            FilterParam::choice(
+                "symmetry",
+                "Mode",
+                &["Off", "Vertical", "Horizontal", "Both", "Radial"],
+                self.mode.index(),
+            )
+            .in_group("Symmetry"),
+            FilterParam::new(
+                "symmetry-segments",
+                "Radial segments",
+                2.0,
+                32.0,
+                1.0,
+                self.segments as f64,
+            )
+            .in_group("Symmetry"),
+            FilterParam::new("symmetry-x", "Center X", 0.0, 1.0, 0.01, self.center_x)
+                .in_group("Symmetry"),
+            FilterParam::new("symmetry-y", "Center Y", 0.0, 1.0, 0.01, self.center_y)
+                .in_group("Symmetry"),
+        ]
+    }
+
+    // Retorna false para chaves que nao sao da simetria.
+    pub fn set_param(&mut self, key: &str, value: f64) -> bool {
+        match key {
+            "symmetry" => {
+                let index = (value.round().max(0.0) as usize).min(SymmetryMode::ALL.len() - 1);
+                self.mode = SymmetryMode::ALL[index];
+            }
+            "symmetry-segments" => self.segments = value.round().clamp(2.0, 32.0) as u32,
+            "symmetry-x" => self.center_x = value.clamp(0.0, 1.0),
+            "symmetry-y" => self.center_y = value.clamp(0.0, 1.0),
+            _ => return false,
+        }
+        true
+    }
+
+    fn center(&self, width: f64, height: f64) -> (f64, f64) {
+        (self.center_x * width, self.center_y * height)
+    }
+
+    // Uma matriz por copia do carimbo, em coordenadas da imagem; a
+    // primeira e sempre a identidade (o proprio traco).
+    pub fn transforms(&self, width: f64, height: f64) -> Vec<Matrix> {
+        let (cx, cy) = self.center(width, height);
+        let mirror_x = Matrix::new(-1.0, 0.0, 0.0, 1.0, 2.0 * cx, 0.0);
+        let mirror_y = Matrix::new(1.0, 0.0, 0.0, -1.0, 0.0, 2.0 * cy);
+        match self.mode {
+            SymmetryMode::Off => vec![Matrix::identity()],
+            SymmetryMode::Vertical => vec![Matrix::identity(), mirror_x],
+            SymmetryMode::Horizontal => vec![Matrix::identity(), mirror_y],
+            SymmetryMode::Both => vec![
+                Matrix::identity(),
+                mirror_x,
+                mirror_y,
+                Matrix::multiply(&mirror_x, &mirror_y),
+            ],
+            SymmetryMode::Radial => (0..self.segments)
+                .map(|i| {
+                    let mut matrix = Matrix::identity();
+                    matrix.translate(cx, cy);
+                    matrix.rotate(TAU * i as f64 / self.segments as f64);
+                    matrix.translate(-cx, -cy);
+                    matrix
+                })
+                .collect(),
+        }
+    }
+
+    // Guia tracejada nos eixos; `ctx` ja esta em coordenadas da imagem.
+    pub fn draw_guide(&self, ctx: &Context, width: f64, height: f64, zoom: f64) {
+        if self.mode == SymmetryMode::Off {
+            return;
+        }
+        let (cx, cy) = self.center(width, height);
+        ctx.save().unwrap();
+        ctx.rectangle(0.0, 0.0, width, height);
+        ctx.clip();
+        ctx.set_source_rgba(0.0, 0.6, 1.0, 0.7);
+        ctx.set_line_width(1.0 / zoom);
+        ctx.set_dash(&[6.0 / zoom, 4.0 / zoom], 0.0);
+
+        if matches!(self.mode, SymmetryMode::Vertical | SymmetryMode::Both) {
+            ctx.move_to(cx, 0.0);
+            ctx.line_to(cx, height);
+        }
+        if matches!(self.mode, SymmetryMode::Horizontal | SymmetryMode::Both) {
+            ctx.move_to(0.0, cy);
+            ctx.line_to(width, cy);
+        }
+        if self.mode == SymmetryMode::Radial {
+            let length = width.hypot(height);
+            for i in 0..self.segments {
+                let angle = TAU * i as f64 / self.segments as f64 - TAU / 4.0;
+                ctx.move_to(cx, cy);
+                ctx.line_to(cx + angle.cos() * length, cy + angle.sin() * length);
+            }
+        }
+        ctx.stroke().unwrap();
+
+        ctx.set_dash(&[], 0.0);
+        ctx.arc(cx, cy, 4.0 / zoom, 0.0, TAU);
+        ctx.stroke().unwrap();
+        ctx.restore().unwrap();
+    }
+}
@@ -0,0 +1,45 @@
+use super::{Filter, FilterParam, convolve_separable};
+use crate::core::pixel_buffer::PixelBuffer;
+
+pub struct BoxBlur {
+    radius: f64,
+}
+
+impl BoxBlur {
+    pub fn new() -> Self {
+        BoxBlur { radius: 2.0 }
+    }
+}
+
+impl Filter for BoxBlur {
+    fn id(&self) -> &'static str {
+        "box-blur"
+    }
+
+    fn name(&self) -> &'static str {
+        "Box Blur"
+    }
+
+    fn params(&self) -> Vec<FilterParam> {
+        vec![FilterParam::new("radius", "Radius", 0.0, 50.0, 1.0, self.radius)]
+    }
+
+    fn set_param(&mut self, key: &str, value: f64) {
+        if key == "radius" {
+            self.radius = value.round();
+        }
+    }
+
+    fn apply(&self, buffer: &mut PixelBuffer) {
+        let size = self.radius as usize * 2 + 1;
+        let kernel = vec![1.0 / size as f32; size];
+        let pixels = buffer.to_rgba_f32();
+        let blurred = convolve_separable(
+            &pixels,
+            buffer.width as usize,
+            buffer.height as usize,
+            &kernel,
+        );
+        buffer.write_rgba_f32(&blurred);
+    }
+}
@@ -0,0 +1,38 @@
+use super::{Filter, FilterParam, gaussian_blur};
+use crate::core::pixel_buffer::PixelBuffer;
+
+pub struct GaussianBlur {
+    radius: f64,
+}
+
+impl GaussianBlur {
+    pub fn new() -> Self {
+        GaussianBlur { radius: 3.0 }
+    }
+}
+
+impl Filter for GaussianBlur {
+    fn id(&self) -> &'static str {
+        "gaussian-blur"
+    }
+
+    fn name(&self) -> &'static str {
+        "Gaussian Blur"
+    }
+
+    fn params(&self) -> Vec<FilterParam> {
+        vec![FilterParam::new("radius", "Radius", 0.0, 100.0, 0.5, self.radius)]
+    }
+
+    fn set_param(&mut self, key: &str, value: f64) {
+        if key == "radius" {
+            self.radius = value;
+        }
+    }
+
+    fn apply(&self, buffer: &mut PixelBuffer) {
+        // radius ~ 3 sigma
+        let blurred = gaussian_blur(buffer, self.radius / 3.0);
+        buffer.write_rgba_f32(&blurred);
+    }
+}
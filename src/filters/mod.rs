@@ -0,0 +1,180 @@
+mod box_blur;
+pub use box_blur::BoxBlur;
+
+mod gaussian_blur;
+pub use gaussian_blur::GaussianBlur;
+
+mod motion_blur;
+pub use motion_blur::MotionBlur;
+
+mod sharpen;
+pub use sharpen::Sharpen;
+
+mod unsharp_mask;
+pub use unsharp_mask::UnsharpMask;
+
+use crate::{core::pixel_buffer::PixelBuffer, geometry::Curve};
+
+// Como o parametro aparece na interface; o valor continua sendo um f64.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamKind {
+    Range,
+    // 0 desligado, 1 ligado.
+    Toggle,
+    // Indice na lista de opcoes.
+    Choice(&'static [&'static str]),
+}
+
+#[derive(Debug, Clone)]
+pub struct FilterParam {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+    pub value: f64,
+    pub group: Option<&'static str>,
+    pub kind: ParamKind,
+}
+
+impl FilterParam {
+    pub fn new(key: &'static str, label: &'static str, min: f64, max: f64, step: f64, value: f64) -> Self {
+        FilterParam {
+            key,
+            label,
+            min,
+            max,
+            step,
+            value,
+            group: None,
+            kind: ParamKind::Range,
+        }
+    }
+
+    pub fn toggle(key: &'static str, label: &'static str, value: bool) -> Self {
+        FilterParam {
+            kind: ParamKind::Toggle,
+            ..Self::new(key, label, 0.0, 1.0, 1.0, if value { 1.0 } else { 0.0 })
+        }
+    }
+
+    pub fn choice(
+        key: &'static str,
+        label: &'static str,
+        options: &'static [&'static str],
+        index: usize,
+    ) -> Self {
+        let max = options.len().saturating_sub(1) as f64;
+        FilterParam {
+            kind: ParamKind::Choice(options),
+            ..Self::new(key, label, 0.0, max, 1.0, index as f64)
+        }
+    }
+
+    pub fn in_group(mut self, group: &'static str) -> Self {
+        self.group = Some(group);
+        self
+    }
+}
+
+pub trait Filter {
+    fn id(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    fn params(&self) -> Vec<FilterParam>;
+    fn set_param(&mut self, key: &str, value: f64);
+    fn apply(&self, buffer: &mut PixelBuffer);
+
+    fn has_preview(&self) -> bool {
+        true
+    }
+
+    // Canais editaveis por curva (ex.: ajuste de curvas).
+    fn curve_channels(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn curve(&self, _channel: usize) -> Option<&Curve> {
+        None
+    }
+
+    fn set_curve(&mut self, _channel: usize, _curve: Curve) {}
+}
+
+pub fn all() -> Vec<Box<dyn Filter>> {
+    vec![
+        Box::new(GaussianBlur::new()),
+        Box::new(BoxBlur::new()),
+        Box::new(MotionBlur::new()),
+        Box::new(Sharpen::new()),
+        Box::new(UnsharpMask::new()),
+    ]
+}
+
+pub fn create(id: &str) -> Option<Box<dyn Filter>> {
+    all().into_iter().find(|filter| filter.id() == id)
+}
+
+// Convolucao separavel com as bordas "esticadas", em RGBA pre-multiplicado.
+pub(crate) fn convolve_separable(
+    pixels: &[[f32; 4]],
+    width: usize,
+    height: usize,
+    kernel: &[f32],
+) -> Vec<[f32; 4]> {
+    let radius = (kernel.len() / 2) as isize;
+    let mut horizontal = vec![[0.0f32; 4]; pixels.len()];
+    let mut out = vec![[0.0f32; 4]; pixels.len()];
+
+    for y in 0..height {
+        for x in 0..width {
+            let mut acc = [0.0f32; 4];
+            for (k, weight) in kernel.iter().enumerate() {
+                let sx = (x as isize + k as isize - radius).clamp(0, width as isize - 1) as usize;
+                let px = pixels[y * width + sx];
+                for c in 0..4 {
+                    acc[c] += px[c] * weight;
+                }
+            }
+            horizontal[y * width + x] = acc;
+        }
+    }
+
+    for y in 0..height {
+        for x in 0..width {
+            let mut acc = [0.0f32; 4];
+            for (k, weight) in kernel.iter().enumerate() {
+                let sy = (y as isize + k as isize - radius).clamp(0, height as isize - 1) as usize;
+                let px = horizontal[sy * width + x];
+                for c in 0..4 {
+                    acc[c] += px[c] * weight;
+                }
+            }
+            out[y * width + x] = acc;
+        }
+    }
+
+    out
+}
+
+pub(crate) fn gaussian_kernel(sigma: f64) -> Vec<f32> {
+    if sigma <= 0.0 {
+        return vec![1.0];
+    }
+    let radius = (sigma * 3.0).ceil() as i32;
+    let mut kernel: Vec<f32> = (-radius..=radius)
+        .map(|i| (-((i * i) as f64) / (2.0 * sigma * sigma)).exp() as f32)
+        .collect();
+    let sum: f32 = kernel.iter().sum();
+    kernel.iter_mut().for_each(|w| *w /= sum);
+    kernel
+}
+
+pub(crate) fn gaussian_blur(buffer: &PixelBuffer, sigma: f64) -> Vec<[f32; 4]> {
+    let pixels = buffer.to_rgba_f32();
+    convolve_separable(
+        &pixels,
+        buffer.width as usize,
+        buffer.height as usize,
+        &gaussian_kernel(sigma),
+    )
+}
@@ -0,0 +1,73 @@
+use super::{Filter, FilterParam};
+use crate::core::pixel_buffer::PixelBuffer;
+
+pub struct MotionBlur {
+    length: f64,
+    angle: f64,
+}
+
+impl MotionBlur {
+    pub fn new() -> Self {
+        MotionBlur {
+            length: 10.0,
+            angle: 0.0,
+        }
+    }
+}
+
+impl Filter for MotionBlur {
+    fn id(&self) -> &'static str {
+        "motion-blur"
+    }
+
+    fn name(&self) -> &'static str {
+        "Motion Blur"
+    }
+
+    fn params(&self) -> Vec<FilterParam> {
+        vec![
+            FilterParam::new("length", "Length", 0.0, 200.0, 1.0, self.length),
+            FilterParam::new("angle", "Angle", -180.0, 180.0, 1.0, self.angle),
+        ]
+    }
+
+    fn set_param(&mut self, key: &str, value: f64) {
+        match key {
+            "length" => self.length = value,
+            "angle" => self.angle = value,
+            _ => {}
+        }
+    }
+
+    fn apply(&self, buffer: &mut PixelBuffer) {
+        let samples = self.length.round() as i32;
+        if samples < 1 {
+            return;
+        }
+
+        let width = buffer.width as usize;
+        let height = buffer.height as usize;
+        let pixels = buffer.to_rgba_f32();
+        let (sin, cos) = self.angle.to_radians().sin_cos();
+        let mut out = vec![[0.0f32; 4]; pixels.len()];
+
+        for y in 0..height {
+            for x in 0..width {
+                let mut acc = [0.0f32; 4];
+                for i in 0..=samples {
+                    let t = i as f64 - samples as f64 / 2.0;
+                    let sx = (x as f64 + cos * t).round().clamp(0.0, width as f64 - 1.0) as usize;
+                    let sy = (y as f64 + sin * t).round().clamp(0.0, height as f64 - 1.0) as usize;
+                    let px = pixels[sy * width + sx];
+                    for c in 0..4 {
+                        acc[c] += px[c];
+                    }
+                }
+                let n = (samples + 1) as f32;
+                out[y * width + x] = acc.map(|v| v / n);
+            }
+        }
+
+        buffer.write_rgba_f32(&out);
+    }
+}
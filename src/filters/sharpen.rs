@@ -0,0 +1,58 @@
+use super::{Filter, FilterParam};
+use crate::core::pixel_buffer::PixelBuffer;
+
+pub struct Sharpen {
+    amount: f64,
+}
+
+impl Sharpen {
+    pub fn new() -> Self {
+        Sharpen { amount: 0.5 }
+    }
+}
+
+impl Filter for Sharpen {
+    fn id(&self) -> &'static str {
+        "sharpen"
+    }
+
+    fn name(&self) -> &'static str {
+        "Sharpen"
+    }
+
+    fn params(&self) -> Vec<FilterParam> {
+        vec![FilterParam::new("amount", "Amount", 0.0, 5.0, 0.05, self.amount)]
+    }
+
+    fn set_param(&mut self, key: &str, value: f64) {
+        if key == "amount" {
+            self.amount = value;
+        }
+    }
+
+    fn apply(&self, buffer: &mut PixelBuffer) {
+        let width = buffer.width as isize;
+        let height = buffer.height as isize;
+        let pixels = buffer.to_rgba_f32();
+        let amount = self.amount as f32;
+        let at = |x: isize, y: isize| {
+            pixels[(y.clamp(0, height - 1) * width + x.clamp(0, width - 1)) as usize]
+        };
+
+        let mut out = pixels.clone();
+        for y in 0..height {
+            for x in 0..width {
+                let center = at(x, y);
+                let neighbours = [at(x - 1, y), at(x + 1, y), at(x, y - 1), at(x, y + 1)];
+                let mut px = center;
+                for c in 0..3 {
+                    let sum: f32 = neighbours.iter().map(|n| n[c]).sum();
+                    px[c] = (center[c] * (1.0 + 4.0 * amount) - sum * amount).clamp(0.0, center[3]);
+                }
+                out[(y * width + x) as usize] = px;
+            }
+        }
+
+        buffer.write_rgba_f32(&out);
+    }
+}
@@ -0,0 +1,69 @@
+use super::{Filter, FilterParam, gaussian_blur};
+use crate::core::pixel_buffer::PixelBuffer;
+
+pub struct UnsharpMask {
+    radius: f64,
+    amount: f64,
+    threshold: f64,
+}
+
+impl UnsharpMask {
+    pub fn new() -> Self {
+        UnsharpMask {
+            radius: 3.0,
+            amount: 0.5,
+            threshold: 0.0,
+        }
+    }
+}
+
+impl Filter for UnsharpMask {
+    fn id(&self) -> &'static str {
+        "unsharp-mask"
+    }
+
+    fn name(&self) -> &'static str {
+        "Unsharp Mask"
+    }
+
+    fn params(&self) -> Vec<FilterParam> {
+        vec![
+            FilterParam::new("radius", "Radius", 0.1, 100.0, 0.1, self.radius),
+            FilterParam::new("amount", "Amount", 0.0, 5.0, 0.05, self.amount),
+            FilterParam::new("threshold", "Threshold", 0.0, 255.0, 1.0, self.threshold),
+        ]
+    }
+
+    fn set_param(&mut self, key: &str, value: f64) {
+        match key {
+            "radius" => self.radius = value,
+            "amount" => self.amount = value,
+            "threshold" => self.threshold = value,
+            _ => {}
+        }
+    }
+
+    fn apply(&self, buffer: &mut PixelBuffer) {
+        let original = buffer.to_rgba_f32();
+        let blurred = gaussian_blur(buffer, self.radius / 3.0);
+        let amount = self.amount as f32;
+        let threshold = (self.threshold / 255.0) as f32;
+
+        let out: Vec<[f32; 4]> = original
+            .iter()
+            .zip(blurred.iter())
+            .map(|(orig, blur)| {
+                let mut px = *orig;
+                for c in 0..3 {
+                    let diff = orig[c] - blur[c];
+                    if diff.abs() >= threshold {
+                        px[c] = (orig[c] + diff * amount).clamp(0.0, orig[3]);
+                    }
+                }
+                px
+            })
+            .collect();
+
+        buffer.write_rgba_f32(&out);
+    }
+}
@@ -0,0 +1,221 @@
+// Distancia minima em x entre dois pontos de controle
+const MIN_GAP: f64 = 1e-3;
+
+// Curva monotona definida por pontos de controle em 0..1 (interpolacao Fritsch-Carlson).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Curve {
+    points: Vec<(f64, f64)>,
+}
+
+impl Curve {
+    pub fn linear() -> Self {
+        Curve {
+            points: vec![(0.0, 0.0), (1.0, 1.0)],
+        }
+    }
+
+    pub fn new(points: Vec<(f64, f64)>) -> Self {
+        let mut curve = Curve { points };
+        curve.normalize();
+        curve
+    }
+
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.points
+            .iter()
+            .all(|(x, y)| (x - y).abs() < f64::EPSILON)
+    }
+
+    // Insere ou move um ponto; retorna o indice final do ponto. Um ponto
+    // arrastado fica preso entre os vizinhos, sem trocar de indice.
+    pub fn set_point(&mut self, index: Option<usize>, point: (f64, f64)) -> usize {
+        let point = (point.0.clamp(0.0, 1.0), point.1.clamp(0.0, 1.0));
+        if let Some(i) = index.filter(|&i| i < self.points.len()) {
+            let lo = if i > 0 {
+                self.points[i - 1].0 + MIN_GAP
+            } else {
+                0.0
+            };
+            let hi = match self.points.get(i + 1) {
+                Some(next) => next.0 - MIN_GAP,
+                None => 1.0,
+            };
+            self.points[i] = (point.0.clamp(lo, hi.max(lo)), point.1);
+            return i;
+        }
+        // Um clique sobre um x ja ocupado move o ponto existente
+        if let Some(i) = self
+            .points
+            .iter()
+            .position(|p| (p.0 - point.0).abs() < MIN_GAP)
+        {
+            self.points[i].1 = point.1;
+            return i;
+        }
+        let i = self.points.partition_point(|p| p.0 < point.0);
+        self.points.insert(i, point);
+        i
+    }
+
+    pub fn remove_point(&mut self, index: usize) {
+        if self.points.len() > 2 && index < self.points.len() {
+            self.points.remove(index);
+        }
+    }
+
+    pub fn nearest(&self, x: f64) -> Option<usize> {
+        self.points
+            .iter()
+            .enumerate()
+            .min_by(|a, b| (a.1.0 - x).abs().total_cmp(&(b.1.0 - x).abs()))
+            .map(|(i, _)| i)
+    }
+
+    fn normalize(&mut self) {
+        self.points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self.points.dedup_by(|a, b| (a.0 - b.0).abs() < MIN_GAP);
+        if self.points.is_empty() {
+            self.points = Curve::linear().points;
+        }
+    }
+
+    pub fn eval(&self, x: f64) -> f64 {
+        let pts = &self.points;
+        let n = pts.len();
+        if n == 1 {
+            return pts[0].1;
+        }
+        if x <= pts[0].0 {
+            return pts[0].1;
+        }
+        if x >= pts[n - 1].0 {
+            return pts[n - 1].1;
+        }
+
+        let slopes: Vec<f64> = pts
+            .windows(2)
+            .map(|w| (w[1].1 - w[0].1) / (w[1].0 - w[0].0))
+            .collect();
+        let tangent = |i: usize| {
+            if i == 0 {
+                slopes[0]
+            } else if i == n - 1 {
+                slopes[n - 2]
+            } else if slopes[i - 1] * slopes[i] <= 0.0 {
+                0.0
+            } else {
+                let h0 = pts[i].0 - pts[i - 1].0;
+                let h1 = pts[i + 1].0 - pts[i].0;
+                3.0 * (h0 + h1) / ((2.0 * h1 + h0) / slopes[i - 1] + (h1 + 2.0 * h0) / slopes[i])
+            }
+        };
+
+        let i = pts.windows(2).position(|w| x < w[1].0).unwrap_or(n - 2);
+        let (x0, y0) = pts[i];
+        let (x1, y1) = pts[i + 1];
+        let h = x1 - x0;
+        let t = (x - x0) / h;
+        let t2 = t * t;
+        let t3 = t2 * t;
+
+        let value = (2.0 * t3 - 3.0 * t2 + 1.0) * y0
+            + (t3 - 2.0 * t2 + t) * h * tangent(i)
+            + (-2.0 * t3 + 3.0 * t2) * y1
+            + (t3 - t2) * h * tangent(i + 1);
+        value.clamp(0.0, 1.0)
+    }
+
+    pub fn lookup_table(&self) -> [f32; 256] {
+        let mut lut = [0.0f32; 256];
+        for (i, v) in lut.iter_mut().enumerate() {
+            *v = self.eval(i as f64 / 255.0) as f32;
+        }
+        lut
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_sorts_and_merges_points() {
+        let curve = Curve::new(vec![(1.0, 1.0), (0.0, 0.0), (0.5, 0.2), (0.5, 0.9)]);
+        assert_eq!(curve.points().len(), 3);
+        assert_eq!(curve.points()[0], (0.0, 0.0));
+        assert_eq!(curve.points()[2], (1.0, 1.0));
+        assert!(Curve::linear().is_identity());
+        assert!(!curve.is_identity());
+    }
+
+    #[test]
+    fn inserts_in_order() {
+        let mut curve = Curve::linear();
+        assert_eq!(curve.set_point(None, (0.5, 0.8)), 1);
+        assert_eq!(curve.set_point(None, (0.25, 0.3)), 1);
+        assert_eq!(curve.points()[2], (0.5, 0.8));
+        // Fora de 0..1 o ponto e preso na borda.
+        let index = curve.set_point(None, (0.75, 2.0));
+        assert_eq!(curve.points()[index], (0.75, 1.0));
+    }
+
+    #[test]
+    fn click_on_existing_x_moves_that_point() {
+        let mut curve = Curve::linear();
+        let index = curve.set_point(None, (1.0, 0.4));
+        assert_eq!(index, 1);
+        assert_eq!(curve.points(), &[(0.0, 0.0), (1.0, 0.4)]);
+    }
+
+    #[test]
+    fn dragged_point_stays_between_neighbours() {
+        let mut curve = Curve::new(vec![(0.0, 0.0), (0.5, 0.5), (1.0, 1.0)]);
+        // Arrastado para alem do vizinho: para logo antes dele, mesmo indice.
+        assert_eq!(curve.set_point(Some(1), (1.0, 0.7)), 1);
+        assert_eq!(curve.points().len(), 3);
+        assert_eq!(curve.points()[1], (1.0 - MIN_GAP, 0.7));
+        assert_eq!(curve.set_point(Some(1), (-1.0, 0.2)), 1);
+        assert_eq!(curve.points()[1], (MIN_GAP, 0.2));
+        // As pontas andam ate a borda.
+        assert_eq!(curve.set_point(Some(2), (1.5, 0.9)), 2);
+        assert_eq!(curve.points()[2], (1.0, 0.9));
+    }
+
+    #[test]
+    fn keeps_at_least_two_points() {
+        let mut curve = Curve::new(vec![(0.0, 0.0), (0.5, 0.5), (1.0, 1.0)]);
+        curve.remove_point(1);
+        curve.remove_point(0);
+        assert_eq!(curve.points().len(), 2);
+    }
+
+    #[test]
+    fn evaluates_endpoints_and_outside_range() {
+        let curve = Curve::new(vec![(0.2, 0.1), (0.6, 0.7), (0.9, 0.8)]);
+        assert_eq!(curve.eval(0.0), 0.1);
+        assert_eq!(curve.eval(0.2), 0.1);
+        assert_eq!(curve.eval(0.9), 0.8);
+        assert_eq!(curve.eval(1.0), 0.8);
+        assert!((curve.eval(0.6) - 0.7).abs() < 1e-9);
+
+        let lut = Curve::linear().lookup_table();
+        assert_eq!(lut[0], 0.0);
+        assert_eq!(lut[255], 1.0);
+        assert!((lut[128] - 128.0 / 255.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn stays_monotone_between_points() {
+        let curve = Curve::new(vec![(0.0, 0.0), (0.3, 0.8), (0.4, 0.85), (1.0, 1.0)]);
+        let mut previous = curve.eval(0.0);
+        for i in 1..=100 {
+            let value = curve.eval(i as f64 / 100.0);
+            assert!(value >= previous - 1e-9);
+            previous = value;
+        }
+    }
+}
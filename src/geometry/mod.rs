@@ -1,2 +1,8 @@
 mod point;
-pub use point::Point;
\ No newline at end of file
+pub use point::Point;
+
+mod rect;
+pub use rect::Rect;
+
+mod curve;
+pub use curve::Curve;
@@ -1,4 +1,7 @@
 pub const OPEN_IMAGE: &str = "open-image";
+pub const IMPORT_BRUSHES: &str = "import-brushes";
+pub const SAVE_BRUSH_PRESET: &str = "save-brush-preset";
+pub const EXPORT_BRUSH_PRESETS: &str = "export-brush-presets";
 pub const ZOOM_IN: &str = "zoom-in";
 pub const ZOOM_OUT: &str = "zoom-out";
 pub const EXIT: &str = "exit";
@@ -7,6 +10,11 @@ pub const ROTATE_RIGHT: &str = "rotate-right";
 pub const TOOL_PAN: &str = "tool-pan";
 pub const TOOL_BRUSH: &str = "tool-brush";
 pub const TOGGLE_ACTION: &str = "toggle-action";
+pub const CLONE_ALIGNED: &str = "clone-aligned";
+pub const LOAD_PAPER_TEXTURE: &str = "load-paper-texture";
+pub const DEFINE_BRUSH: &str = "define-brush";
+pub const FILTER: &str = "filter";
+pub const ADJUSTMENT: &str = "adjustment";
 
 pub mod app {
     use const_format::concatcp;
@@ -14,10 +22,18 @@ pub mod app {
     const APP_PREFIX: &str = "app.";
     pub const EXIT: &str = concatcp!(APP_PREFIX, super::EXIT);
     pub const OPEN_IMAGE: &str = concatcp!(APP_PREFIX, super::OPEN_IMAGE);
+    pub const IMPORT_BRUSHES: &str = concatcp!(APP_PREFIX, super::IMPORT_BRUSHES);
+    pub const SAVE_BRUSH_PRESET: &str = concatcp!(APP_PREFIX, super::SAVE_BRUSH_PRESET);
+    pub const EXPORT_BRUSH_PRESETS: &str = concatcp!(APP_PREFIX, super::EXPORT_BRUSH_PRESETS);
     pub const ZOOM_IN: &str = concatcp!(APP_PREFIX, super::ZOOM_IN);
     pub const ZOOM_OUT: &str = concatcp!(APP_PREFIX, super::ZOOM_OUT);
     pub const ROTATE_LEFT: &str = concatcp!(APP_PREFIX, super::ROTATE_LEFT);
     pub const ROTATE_RIGHT: &str = concatcp!(APP_PREFIX, super::ROTATE_RIGHT);
   
     pub const TOGGLE_ACTION: &str = concatcp!(APP_PREFIX, super::TOGGLE_ACTION);
+    pub const CLONE_ALIGNED: &str = concatcp!(APP_PREFIX, super::CLONE_ALIGNED);
+    pub const LOAD_PAPER_TEXTURE: &str = concatcp!(APP_PREFIX, super::LOAD_PAPER_TEXTURE);
+    pub const DEFINE_BRUSH: &str = concatcp!(APP_PREFIX, super::DEFINE_BRUSH);
+    pub const FILTER: &str = concatcp!(APP_PREFIX, super::FILTER);
+    pub const ADJUSTMENT: &str = concatcp!(APP_PREFIX, super::ADJUSTMENT);
 }
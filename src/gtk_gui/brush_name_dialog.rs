@@ -0,0 +1,94 @@
+use crate::program::Program;
+use gtk::{
+    ApplicationWindow, Button, Entry, Label, Orientation, Window,
+    glib::{self, clone},
+    prelude::{BoxExt, ButtonExt, EditableExt, EntryExt, GtkWindowExt},
+};
+use std::rc::Rc;
+
+// Pede o nome antes de criar um pincel a partir da selecao ou da imagem.
+pub struct BrushNameDialog {
+    window: Window,
+}
+
+impl BrushNameDialog {
+    pub fn new(parent: &ApplicationWindow, program: Rc<Program>) -> Self {
+        let content = gtk::Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(6)
+            .margin_top(12)
+            .margin_bottom(12)
+            .margin_start(12)
+            .margin_end(12)
+            .build();
+
+        let window = Window::builder()
+            .transient_for(parent)
+            .modal(true)
+            .title("Define Brush")
+            .default_width(280)
+            .child(&content)
+            .build();
+
+        let default_name = if program.has_selection() {
+            "Selection"
+        } else {
+            "Image"
+        };
+        let entry = Entry::builder().text(default_name).build();
+        content.append(
+            &Label::builder()
+                .label("Brush name")
+                .halign(gtk::Align::Start)
+                .build(),
+        );
+        content.append(&entry);
+
+        let buttons = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(6)
+            .halign(gtk::Align::End)
+            .build();
+        let cancel = Button::with_label("Cancel");
+        let define = Button::with_label("Define");
+        buttons.append(&cancel);
+        buttons.append(&define);
+        content.append(&buttons);
+
+        let accept = Rc::new(clone!(
+            #[strong]
+            program,
+            #[weak]
+            entry,
+            #[weak]
+            window,
+            move || {
+                let name = entry.text();
+                let name = name.trim();
+                if name.is_empty() {
+                    return;
+                }
+                program.define_brush(name);
+                window.close();
+            }
+        ));
+
+        cancel.connect_clicked(clone!(
+            #[weak]
+            window,
+            move |_| window.close()
+        ));
+        define.connect_clicked(clone!(
+            #[strong]
+            accept,
+            move |_| accept()
+        ));
+        entry.connect_activate(move |_| accept());
+
+        BrushNameDialog { window }
+    }
+
+    pub fn show(&self) {
+        self.window.present();
+    }
+}
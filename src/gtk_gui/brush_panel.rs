@@ -0,0 +1,333 @@
+use crate::{filters::ParamKind, gtk_gui::curve_editor::CurveEditor, program::Program};
+use gtk::{
+    DrawingArea, DropDown, Label, ListBox, Orientation, PolicyType, Scale, ScrolledWindow,
+    SelectionMode, Switch,
+    glib::{self, clone},
+    prelude::{
+        BoxExt, Cast, DrawingAreaExt, DrawingAreaExtManual, ListBoxRowExt, RangeExt, ScaleExt,
+        WidgetExt,
+    },
+};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+const THUMBNAIL_WIDTH: i32 = 96;
+const THUMBNAIL_HEIGHT: i32 = 48;
+const PRESSURE_CURVE_SIZE: i32 = 200;
+
+// Painel de opcoes do pincel: lista de pinceis e ajustes ao vivo.
+pub struct BrushPanel {
+    root: gtk::Box,
+}
+
+impl BrushPanel {
+    pub fn new(program: Rc<Program>) -> Self {
+        let root = gtk::Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(6)
+            .width_request(240)
+            .margin_start(6)
+            .margin_end(6)
+            .build();
+
+        let list = ListBox::builder()
+            .selection_mode(SelectionMode::Single)
+            .build();
+        let params_box = gtk::Box::new(Orientation::Vertical, 6);
+        let names: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+        let filling = Rc::new(Cell::new(false));
+
+        list.connect_row_selected(clone!(
+            #[strong]
+            program,
+            #[strong]
+            names,
+            #[strong]
+            filling,
+            #[weak]
+            params_box,
+            move |_, row| {
+                if filling.get() {
+                    return;
+                }
+                let Some(row) = row else {
+                    return;
+                };
+                if let Some(name) = names.borrow().get(row.index() as usize) {
+                    program.select_brush(name);
+                }
+                Self::fill_params(&params_box, &program);
+            }
+        ));
+
+        Self::fill_brushes(&list, &program, &names, &filling);
+        Self::fill_params(&params_box, &program);
+
+        let stats = Label::builder()
+            .halign(gtk::Align::Start)
+            .wrap(true)
+            .css_classes(["dim-label"])
+            .build();
+
+        stats.set_label(&program.brush_cache_stats());
+
+        // O programa guarda os ouvintes, entao eles so seguram referencias fracas.
+        program.connect_brushes_changed(clone!(
+            #[weak]
+            program,
+            #[strong]
+            names,
+            #[strong]
+            filling,
+            #[weak]
+            list,
+            #[weak]
+            params_box,
+            #[weak]
+            stats,
+            move || {
+                Self::fill_brushes(&list, &program, &names, &filling);
+                Self::fill_params(&params_box, &program);
+                stats.set_label(&program.brush_cache_stats());
+            }
+        ));
+        program.connect_cache_changed(clone!(
+            #[weak]
+            program,
+            #[weak]
+            stats,
+            move || stats.set_label(&program.brush_cache_stats())
+        ));
+        program.connect_library_changed(clone!(
+            #[weak]
+            program,
+            move || program.reload_brushes()
+        ));
+
+        let brushes = ScrolledWindow::builder()
+            .hscrollbar_policy(PolicyType::Never)
+            .min_content_height(200)
+            .child(&list)
+            .build();
+        let params = ScrolledWindow::builder()
+            .hscrollbar_policy(PolicyType::Never)
+            .vexpand(true)
+            .child(&params_box)
+            .build();
+        root.append(&brushes);
+        root.append(&params);
+        root.append(&stats);
+
+        BrushPanel { root }
+    }
+
+    pub fn widget(&self) -> &gtk::Box {
+        &self.root
+    }
+
+    fn fill_brushes(
+        list: &ListBox,
+        program: &Program,
+        names: &RefCell<Vec<String>>,
+        filling: &Cell<bool>,
+    ) {
+        filling.set(true);
+        list.remove_all();
+
+        let (brush_names, active) = program.brush_names();
+        for name in &brush_names {
+            let thumbnail = program.brush_thumbnail(name);
+            let area = DrawingArea::builder()
+                .content_width(THUMBNAIL_WIDTH)
+                .content_height(THUMBNAIL_HEIGHT)
+                .build();
+            area.set_draw_func(move |_, ctx, _, _| {
+                if let Some(surface) = &thumbnail
+                    && ctx.set_source_surface(surface, 0.0, 0.0).is_ok()
+                {
+                    let _ = ctx.paint();
+                }
+            });
+
+            let row = gtk::Box::new(Orientation::Horizontal, 6);
+            row.append(&area);
+            row.append(&Label::new(Some(name)));
+            list.append(&row);
+        }
+
+        if let Some(index) = brush_names.iter().position(|n| *n == active) {
+            list.select_row(list.row_at_index(index as i32).as_ref());
+        }
+        *names.borrow_mut() = brush_names;
+        filling.set(false);
+    }
+
+    fn fill_params(params_box: &gtk::Box, program: &Rc<Program>) {
+        while let Some(child) = params_box.first_child() {
+            params_box.remove(&child);
+        }
+
+        let blend_modes = DropDown::from_strings(&program.brush_blend_modes());
+        blend_modes.set_selected(program.brush_blend_mode() as u32);
+        blend_modes.connect_selected_notify(clone!(
+            #[strong]
+            program,
+            move |blend_modes| program.set_brush_blend_mode(blend_modes.selected() as usize)
+        ));
+        params_box.append(
+            &Label::builder()
+                .label("Blend mode")
+                .halign(gtk::Align::Start)
+                .build(),
+        );
+        params_box.append(&blend_modes);
+
+        // Segunda ponta: "None" desliga o recorte.
+        let (names, _) = program.brush_names();
+        let mut choices = vec!["None"];
+        choices.extend(names.iter().map(String::as_str));
+        let dual = DropDown::from_strings(&choices);
+        let selected = program
+            .dual_brush()
+            .and_then(|name| names.iter().position(|n| *n == name))
+            .map_or(0, |index| index + 1);
+        dual.set_selected(selected as u32);
+        dual.connect_selected_notify(clone!(
+            #[strong]
+            program,
+            move |dual| {
+                let index = dual.selected() as usize;
+                program.set_dual_brush(index.checked_sub(1).and_then(|i| names.get(i).cloned()));
+            }
+        ));
+        params_box.append(
+            &Label::builder()
+                .label("Dual brush")
+                .halign(gtk::Align::Start)
+                .build(),
+        );
+        params_box.append(&dual);
+
+        let dynamics = program
+            .brush_dynamics_params()
+            .into_iter()
+            .map(|p| p.in_group("Dynamics"));
+        let mut current_group = None;
+        for param in program.brush_params().into_iter().chain(dynamics) {
+            if param.group != current_group {
+                let heading = Label::builder()
+                    .label(format!("<b>{}</b>", param.group.unwrap_or_default()))
+                    .use_markup(true)
+                    .halign(gtk::Align::Start)
+                    .build();
+                params_box.append(&heading);
+                current_group = param.group;
+            }
+
+            let label = Label::builder()
+                .label(param.label)
+                .halign(gtk::Align::Start)
+                .build();
+            let is_dynamics = param.group == Some("Dynamics");
+            let key = param.key;
+            let widget: gtk::Widget = match param.kind {
+                ParamKind::Range => {
+                    let scale = Scale::with_range(
+                        Orientation::Horizontal,
+                        param.min,
+                        param.max,
+                        param.step,
+                    );
+                    scale.set_value(param.value);
+                    scale.set_draw_value(true);
+                    scale.connect_value_changed(clone!(
+                        #[strong]
+                        program,
+                        move |scale| Self::set_param(&program, is_dynamics, key, scale.value())
+                    ));
+                    scale.upcast()
+                }
+                ParamKind::Toggle => {
+                    let switch = Switch::builder()
+                        .active(param.value >= 0.5)
+                        .halign(gtk::Align::Start)
+                        .build();
+                    switch.connect_active_notify(clone!(
+                        #[strong]
+                        program,
+                        move |switch| {
+                            let value = if switch.is_active() { 1.0 } else { 0.0 };
+                            Self::set_param(&program, is_dynamics, key, value);
+                        }
+                    ));
+                    switch.upcast()
+                }
+                ParamKind::Choice(options) => {
+                    let choices = DropDown::from_strings(options);
+                    choices.set_selected(param.value.round().max(0.0) as u32);
+                    choices.connect_selected_notify(clone!(
+                        #[strong]
+                        program,
+                        move |choices| {
+                            Self::set_param(&program, is_dynamics, key, choices.selected() as f64);
+                        }
+                    ));
+                    choices.upcast()
+                }
+            };
+
+            params_box.append(&label);
+            params_box.append(&widget);
+        }
+
+        Self::fill_pressure(params_box, program);
+    }
+
+    // Curvas da caneta: pressao para tamanho e para opacidade.
+    fn fill_pressure(params_box: &gtk::Box, program: &Rc<Program>) {
+        let target = DropDown::from_strings(&["Size", "Opacity"]);
+        let editor = Rc::new(CurveEditor::new(clone!(
+            #[strong]
+            program,
+            #[weak]
+            target,
+            move |curve| program.set_pressure_curve(target.selected() as usize, curve.clone())
+        )));
+        editor.widget().set_content_width(PRESSURE_CURVE_SIZE);
+        editor.widget().set_content_height(PRESSURE_CURVE_SIZE);
+        if let Some(curve) = program.pressure_curve(0) {
+            editor.set_curve(curve);
+        }
+        target.connect_selected_notify(clone!(
+            #[strong]
+            program,
+            #[strong]
+            editor,
+            move |target| {
+                if let Some(curve) = program.pressure_curve(target.selected() as usize) {
+                    editor.set_curve(curve);
+                }
+            }
+        ));
+
+        params_box.append(
+            &Label::builder()
+                .label("<b>Pen pressure</b>")
+                .use_markup(true)
+                .halign(gtk::Align::Start)
+                .build(),
+        );
+        params_box.append(&target);
+        params_box.append(editor.widget());
+    }
+
+    fn set_param(program: &Program, is_dynamics: bool, key: &str, value: f64) {
+        if is_dynamics {
+            program.set_brush_dynamics_param(key, value);
+        } else {
+            program.set_brush_param(key, value);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use crate::geometry::Curve;
+use gtk::{
+    DrawingArea, GestureClick, GestureDrag,
+    glib::clone,
+    prelude::{DrawingAreaExtManual, EventControllerExt, GestureDragExt, GestureSingleExt, WidgetExt},
+};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+const SIZE: i32 = 256;
+const HANDLE_RADIUS: f64 = 8.0;
+
+// Editor de curva: arrastar move/insere pontos, botao direito remove.
+pub struct CurveEditor {
+    area: DrawingArea,
+    curve: Rc<RefCell<Curve>>,
+}
+
+impl CurveEditor {
+    pub fn new<F: Fn(&Curve) + 'static>(on_change: F) -> Self {
+        let area = DrawingArea::builder()
+            .content_width(SIZE)
+            .content_height(SIZE)
+            .build();
+        let curve = Rc::new(RefCell::new(Curve::linear()));
+        let dragging: Rc<Cell<Option<usize>>> = Rc::new(Cell::new(None));
+        let on_change = Rc::new(on_change);
+
+        area.set_draw_func(clone!(
+            #[strong]
+            curve,
+            move |_, ctx, width, height| {
+                Self::draw(ctx, &curve.borrow(), width as f64, height as f64);
+            }
+        ));
+
+        let drag = GestureDrag::new();
+        drag.connect_drag_begin(clone!(
+            #[strong]
+            curve,
+            #[strong]
+            dragging,
+            #[strong]
+            on_change,
+            move |gesture, x, y| {
+                let Some(area) = gesture.widget() else {
+                    return;
+                };
+                let (w, h) = (area.width() as f64, area.height() as f64);
+                let mut curve = curve.borrow_mut();
+                let hit = curve.points().iter().position(|(px, py)| {
+                    (px * w - x).hypot((1.0 - py) * h - y) <= HANDLE_RADIUS
+                });
+                let index = curve.set_point(hit, (x / w, 1.0 - y / h));
+                dragging.set(Some(index));
+                on_change(&curve);
+                area.queue_draw();
+            }
+        ));
+        drag.connect_drag_update(clone!(
+            #[strong]
+            curve,
+            #[strong]
+            dragging,
+            #[strong]
+            on_change,
+            move |gesture, dx, dy| {
+                let (Some(area), Some(index), Some((sx, sy))) =
+                    (gesture.widget(), dragging.get(), gesture.start_point())
+                else {
+                    return;
+                };
+                let (w, h) = (area.width() as f64, area.height() as f64);
+                let mut curve = curve.borrow_mut();
+                let index = curve.set_point(Some(index), ((sx + dx) / w, 1.0 - (sy + dy) / h));
+                dragging.set(Some(index));
+                on_change(&curve);
+                area.queue_draw();
+            }
+        ));
+        drag.connect_drag_end(clone!(
+            #[strong]
+            dragging,
+            move |_, _, _| dragging.set(None)
+        ));
+
+        let remove = GestureClick::new();
+        remove.set_button(3);
+        remove.connect_pressed(clone!(
+            #[strong]
+            curve,
+            #[strong]
+            on_change,
+            move |gesture, _, x, _| {
+                let Some(area) = gesture.widget() else {
+                    return;
+                };
+                let mut curve = curve.borrow_mut();
+                if let Some(index) = curve.nearest(x / area.width() as f64) {
+                    curve.remove_point(index);
+                    on_change(&curve);
+                    area.queue_draw();
+                }
+            }
+        ));
+
+        area.add_controller(drag);
+        area.add_controller(remove);
+
+        CurveEditor { area, curve }
+    }
+
+    pub fn widget(&self) -> &DrawingArea {
+        &self.area
+    }
+
+    pub fn set_curve(&self, curve: Curve) {
+        *self.curve.borrow_mut() = curve;
+        self.area.queue_draw();
+    }
+
+    fn draw(ctx: &gtk::cairo::Context, curve: &Curve, width: f64, height: f64) {
+        ctx.set_source_rgb(0.15, 0.15, 0.15);
+        ctx.paint().unwrap();
+
+        ctx.set_source_rgb(0.3, 0.3, 0.3);
+        ctx.set_line_width(1.0);
+        for i in 1..4 {
+            let t = i as f64 / 4.0;
+            ctx.move_to(t * width, 0.0);
+            ctx.line_to(t * width, height);
+            ctx.move_to(0.0, t * height);
+            ctx.line_to(width, t * height);
+        }
+        ctx.stroke().unwrap();
+
+        ctx.set_source_rgb(0.9, 0.9, 0.9);
+        ctx.set_line_width(2.0);
+        let steps = width.max(2.0) as i32;
+        for i in 0..=steps {
+            let x = i as f64 / steps as f64;
+            let y = curve.eval(x);
+            ctx.line_to(x * width, (1.0 - y) * height);
+        }
+        ctx.stroke().unwrap();
+
+        for (x, y) in curve.points() {
+            ctx.arc(x * width, (1.0 - y) * height, 4.0, 0.0, std::f64::consts::TAU);
+            ctx.fill().unwrap();
+        }
+    }
+}
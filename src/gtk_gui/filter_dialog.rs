@@ -0,0 +1,203 @@
+use crate::{filters::Filter, gtk_gui::curve_editor::CurveEditor, program::Program};
+use gtk::{
+    ApplicationWindow, Button, CheckButton, DrawingArea, DropDown, Label, Orientation,
+    PolicyType, Scale, ScrolledWindow, Window,
+    glib::{self, clone},
+    prelude::{BoxExt, ButtonExt, CheckButtonExt, GtkWindowExt, RangeExt, ScaleExt, WidgetExt},
+};
+use std::{cell::RefCell, rc::Rc};
+
+pub struct FilterDialog {
+    window: Window,
+}
+
+impl FilterDialog {
+    pub fn new(
+        parent: &ApplicationWindow,
+        program: Rc<Program>,
+        drawing: Rc<DrawingArea>,
+        filter: Box<dyn Filter>,
+    ) -> Self {
+        let content = gtk::Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(6)
+            .margin_top(12)
+            .margin_bottom(12)
+            .margin_start(12)
+            .margin_end(12)
+            .build();
+
+        let window = Window::builder()
+            .transient_for(parent)
+            .modal(true)
+            .title(filter.name())
+            .default_width(320)
+            .child(&content)
+            .build();
+
+        let preview = CheckButton::builder()
+            .label("Preview")
+            .active(filter.has_preview())
+            .sensitive(filter.has_preview())
+            .build();
+
+        let params = filter.params();
+        let filter = Rc::new(RefCell::new(filter));
+
+        let update_preview = Rc::new(clone!(
+            #[strong]
+            program,
+            #[strong]
+            drawing,
+            #[strong]
+            filter,
+            #[weak]
+            preview,
+            move || {
+                if preview.is_active() {
+                    program.preview_filter(filter.borrow().as_ref());
+                } else {
+                    program.cancel_filter_preview();
+                }
+                drawing.queue_draw();
+            }
+        ));
+
+        let channels = filter.borrow().curve_channels();
+        if !channels.is_empty() {
+            let channel = DropDown::from_strings(channels);
+            let editor = Rc::new(CurveEditor::new(clone!(
+                #[strong]
+                filter,
+                #[strong]
+                update_preview,
+                #[weak]
+                channel,
+                move |curve| {
+                    filter
+                        .borrow_mut()
+                        .set_curve(channel.selected() as usize, curve.clone());
+                    update_preview();
+                }
+            )));
+            if let Some(curve) = filter.borrow().curve(0) {
+                editor.set_curve(curve.clone());
+            }
+            channel.connect_selected_notify(clone!(
+                #[strong]
+                filter,
+                #[strong]
+                editor,
+                move |channel| {
+                    if let Some(curve) = filter.borrow().curve(channel.selected() as usize) {
+                        editor.set_curve(curve.clone());
+                    }
+                }
+            ));
+
+            content.append(&channel);
+            content.append(editor.widget());
+        }
+
+        let params_box = gtk::Box::new(Orientation::Vertical, 6);
+        let mut current_group = None;
+        for param in params {
+            if param.group.is_some() && param.group != current_group {
+                let heading = Label::builder()
+                    .label(format!("<b>{}</b>", param.group.unwrap_or_default()))
+                    .use_markup(true)
+                    .halign(gtk::Align::Start)
+                    .build();
+                params_box.append(&heading);
+                current_group = param.group;
+            }
+
+            let label = Label::builder()
+                .label(param.label)
+                .halign(gtk::Align::Start)
+                .build();
+            let scale = Scale::with_range(Orientation::Horizontal, param.min, param.max, param.step);
+            scale.set_value(param.value);
+            scale.set_draw_value(true);
+            scale.connect_value_changed(clone!(
+                #[strong]
+                filter,
+                #[strong]
+                update_preview,
+                move |scale| {
+                    filter.borrow_mut().set_param(param.key, scale.value());
+                    update_preview();
+                }
+            ));
+
+            params_box.append(&label);
+            params_box.append(&scale);
+        }
+
+        let scrolled = ScrolledWindow::builder()
+            .hscrollbar_policy(PolicyType::Never)
+            .propagate_natural_height(true)
+            .max_content_height(480)
+            .child(&params_box)
+            .build();
+        content.append(&scrolled);
+
+        preview.connect_toggled(clone!(
+            #[strong]
+            update_preview,
+            move |_| update_preview()
+        ));
+        content.append(&preview);
+
+        let buttons = gtk::Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(6)
+            .halign(gtk::Align::End)
+            .build();
+        let cancel = Button::with_label("Cancel");
+        let apply = Button::with_label("Apply");
+        buttons.append(&cancel);
+        buttons.append(&apply);
+        content.append(&buttons);
+
+        cancel.connect_clicked(clone!(
+            #[weak]
+            window,
+            move |_| window.close()
+        ));
+        apply.connect_clicked(clone!(
+            #[strong]
+            program,
+            #[strong]
+            drawing,
+            #[strong]
+            filter,
+            #[weak]
+            window,
+            move |_| {
+                program.apply_filter(filter.borrow().as_ref());
+                drawing.queue_draw();
+                window.close();
+            }
+        ));
+        window.connect_close_request(clone!(
+            #[strong]
+            program,
+            #[strong]
+            drawing,
+            move |_| {
+                program.cancel_filter_preview();
+                drawing.queue_draw();
+                glib::Propagation::Proceed
+            }
+        ));
+
+        update_preview();
+
+        FilterDialog { window }
+    }
+
+    pub fn show(&self) {
+        self.window.present();
+    }
+}
@@ -1,21 +1,33 @@
 use crate::{
-    core::{app::Tools, event::AppEvents},
+    adjustments,
+    core::{
+        app::Tools,
+        event::{AppEvents, DeviceTool, Key, KeyEvent, Modifiers, PointerEvent},
+    },
+    filters::{self, Filter},
     geometry::Point,
-    gtk_gui::{actions, open_image::OpenImage},
+    gtk_gui::{
+        actions, brush_name_dialog::BrushNameDialog, brush_panel::BrushPanel,
+        filter_dialog::FilterDialog, open_image::OpenImage,
+    },
     program::Program,
 };
 use gtk::{
-    Application, ApplicationWindow, Button, CenterBox, DrawingArea, EventControllerMotion,
-    EventControllerScroll, EventControllerScrollFlags, GestureClick, Image, Label, Orientation,
-    ToggleButton,
+    Application, ApplicationWindow, Button, CenterBox, ColorDialog, ColorDialogButton, DrawingArea, EventControllerMotion,
+    EventController, EventControllerFocus, EventControllerKey, EventControllerScroll, EventControllerScrollFlags,
+    GestureClick, GestureStylus, Image, Label, Orientation, ToggleButton,
     gio::{
         SimpleAction,
         prelude::{ActionExt, ActionMapExt, ApplicationExt},
     },
-    glib::{self, VariantType, clone, variant::ToVariant},
-    prelude::{BoxExt, DrawingAreaExtManual, GtkApplicationExt, GtkWindowExt, WidgetExt},
+    gdk,
+    glib::{self, VariantType, clone, translate::IntoGlib, variant::ToVariant},
+    prelude::{
+        BoxExt, DeviceExt, DrawingAreaExtManual, EventControllerExt, GtkApplicationExt,
+        GtkWindowExt, IsA, WidgetExt,
+    },
 };
-use std::{path::PathBuf, rc::Rc, str::FromStr};
+use std::{path::Path, rc::Rc, str::FromStr, time::Duration};
 
 pub struct MainWindow {
     gtk_app: Application,
@@ -24,6 +36,7 @@ pub struct MainWindow {
     program: Rc<Program>,
     label_zoom: Rc<Label>,
     label_rotate: Rc<Label>,
+    foreground: Rc<ColorDialogButton>,
 }
 
 impl MainWindow {
@@ -40,7 +53,9 @@ impl MainWindow {
         let drawing = DrawingArea::builder().hexpand(true).vexpand(true).build();
         let drawing = Rc::new(drawing);
         let area_drawing = gtk::Box::new(Orientation::Horizontal, 0);
-        area_drawing.append(&Self::make_tool_bar());
+        let (tool_bar, foreground) = Self::make_tool_bar(program.clone());
+        area_drawing.append(&tool_bar);
+        area_drawing.append(BrushPanel::new(program.clone()).widget());
         area_drawing.append(drawing.as_ref());
 
         vbox.append(&header_bar);
@@ -62,6 +77,7 @@ impl MainWindow {
             program: Rc::clone(&program),
             label_zoom: label_zoom,
             label_rotate,
+            foreground: Rc::new(foreground),
         }
     }
 
@@ -112,7 +128,7 @@ impl MainWindow {
             .build()
     }
 
-    fn make_tool_bar() -> gtk::Box {
+    fn make_tool_bar(program: Rc<Program>) -> (gtk::Box, ColorDialogButton) {
         let column = gtk::Box::new(Orientation::Vertical, 0);
 
         let pan = ToggleButton::builder()
@@ -126,10 +142,93 @@ impl MainWindow {
             .action_target(&Tools::Brush.to_string().to_variant())
             .build();
 
+        let clone = ToggleButton::builder()
+            .icon_name("edit-copy-symbolic")
+            .tooltip_markup("Clone Stamp (Ctrl+click sets the source)")
+            .action_name(actions::app::TOGGLE_ACTION)
+            .action_target(&Tools::Clone.to_string().to_variant())
+            .build();
+        let heal = ToggleButton::builder()
+            .icon_name("applications-science-symbolic")
+            .tooltip_markup("Healing Brush (Ctrl+click sets the source)")
+            .action_name(actions::app::TOGGLE_ACTION)
+            .action_target(&Tools::Heal.to_string().to_variant())
+            .build();
+
+        let assistant = ToggleButton::builder()
+            .icon_name("view-grid-symbolic")
+            .tooltip_markup("Assistants (drag the handles)")
+            .action_name(actions::app::TOGGLE_ACTION)
+            .action_target(&Tools::Assistant.to_string().to_variant())
+            .build();
+
+        let select = ToggleButton::builder()
+            .icon_name("edit-select-all-symbolic")
+            .tooltip_markup("Rectangle Select (click or Esc clears)")
+            .action_name(actions::app::TOGGLE_ACTION)
+            .action_target(&Tools::Select.to_string().to_variant())
+            .build();
+
         column.append(&pan);
+        column.append(&select);
         column.append(&brush);
+        column.append(&clone);
+        column.append(&heal);
+        column.append(&assistant);
+
+        let foreground = ColorDialogButton::builder()
+            .dialog(&ColorDialog::new())
+            .rgba(&gdk::RGBA::BLACK)
+            .tooltip_markup("Foreground Color")
+            .build();
+        foreground.connect_rgba_notify(clone!(
+            #[strong]
+            program,
+            move |button| {
+                let rgba = button.rgba();
+                program.set_foreground_color(
+                    rgba.red() as f64,
+                    rgba.green() as f64,
+                    rgba.blue() as f64,
+                );
+            }
+        ));
+        let background = ColorDialogButton::builder()
+            .dialog(&ColorDialog::new())
+            .rgba(&gdk::RGBA::WHITE)
+            .tooltip_markup("Background Color")
+            .build();
+        background.connect_rgba_notify(clone!(
+            #[strong]
+            program,
+            move |button| {
+                let rgba = button.rgba();
+                program.set_background_color(
+                    rgba.red() as f64,
+                    rgba.green() as f64,
+                    rgba.blue() as f64,
+                );
+            }
+        ));
+
+        column.append(&foreground);
+        column.append(&background);
+
+        (column, foreground)
+    }
 
-        column
+    // Mostra no botao a cor de frente trocada pelo conta-gotas.
+    fn sync_colors(program: &Rc<Program>, foreground: &ColorDialogButton) {
+        if !program.state.borrow_mut().take_color_sync() {
+            return;
+        }
+        let color = program.foreground();
+        foreground.set_rgba(&gdk::RGBA::new(
+            color.r as f32,
+            color.g as f32,
+            color.b as f32,
+            1.0,
+        ));
     }
 
     pub fn open_image(&self) {
@@ -168,6 +267,120 @@ impl MainWindow {
             .set_accels_for_action(actions::app::OPEN_IMAGE, &["<Ctrl>O"]);
     }
 
+    pub fn import_brushes(&self) {
+        let window = self.window();
+        let program = self.program.clone();
+
+        let action = SimpleAction::new(actions::IMPORT_BRUSHES, None);
+        action.connect_activate(clone!(
+            #[strong]
+            program,
+            move |_, _| {
+                OpenImage::brushes().run(
+                    &window,
+                    clone!(
+                        #[strong]
+                        program,
+                        move |path| {
+                            if let Some(path) = path {
+                                program.import_brushes(&path);
+                            }
+                        }
+                    ),
+                );
+            }
+        ));
+
+        self.gtk_app.add_action(&action);
+    }
+
+    pub fn load_paper_texture(&self) {
+        let window = self.window();
+        let program = self.program.clone();
+
+        let action = SimpleAction::new(actions::LOAD_PAPER_TEXTURE, None);
+        action.connect_activate(clone!(
+            #[strong]
+            program,
+            move |_, _| {
+                OpenImage::new().run(
+                    &window,
+                    clone!(
+                        #[strong]
+                        program,
+                        move |path| {
+                            if let Some(path) = path {
+                                program.load_paper_texture(&path);
+                            }
+                        }
+                    ),
+                );
+            }
+        ));
+
+        self.gtk_app.add_action(&action);
+    }
+
+    pub fn define_brush(&self) {
+        let program = self.program.clone();
+        let window = self.window.clone();
+
+        let action = SimpleAction::new(actions::DEFINE_BRUSH, None);
+        action.connect_activate(move |_, _| {
+            BrushNameDialog::new(&window, program.clone()).show();
+        });
+
+        self.gtk_app.add_action(&action);
+    }
+
+    fn save_brush_presets(&self) {
+        self.on_register_save_action(
+            actions::SAVE_BRUSH_PRESET,
+            "Save Brush Preset",
+            "brush.mpbrush",
+            |program, path| program.save_brush_preset(path),
+        );
+        self.on_register_save_action(
+            actions::EXPORT_BRUSH_PRESETS,
+            "Export Brush Bundle",
+            "brushes.mpbrushes",
+            |program, path| program.export_brush_presets(path),
+        );
+    }
+
+    fn on_register_save_action(
+        &self,
+        name: &str,
+        title: &'static str,
+        initial_name: &'static str,
+        save: fn(&Program, &Path),
+    ) {
+        let window = self.window();
+        let program = self.program.clone();
+
+        let action = SimpleAction::new(name, None);
+        action.connect_activate(clone!(
+            #[strong]
+            program,
+            move |_, _| {
+                OpenImage::brush_presets(title, initial_name).run_save(
+                    &window,
+                    clone!(
+                        #[strong]
+                        program,
+                        move |path| {
+                            if let Some(path) = path {
+                                save(&program, &path);
+                            }
+                        }
+                    ),
+                );
+            }
+        ));
+
+        self.gtk_app.add_action(&action);
+    }
+
     pub fn exit(&self) {
         let app = self.gtk_app.clone();
         let action = SimpleAction::new(actions::EXIT, None);
@@ -200,27 +413,75 @@ impl MainWindow {
 
     pub fn connect_events(&self) {
         self.open_image();
+        self.import_brushes();
+        self.save_brush_presets();
+        self.load_paper_texture();
+        self.define_brush();
         self.register_zoom_action();
         self.register_rotate_action();
         self.register_tools_action();
+        self.register_filter_action();
         self.exit();
     }
 
+    // Envia AppEvents::Tick enquanto a ferramenta pedir (ex.: aerografo).
+    fn start_ticks(program: &Rc<Program>, drawing: &Rc<DrawingArea>) {
+        let Some(interval) = program.state.borrow().tick_interval() else {
+            return;
+        };
+        let generation = program.state.borrow().tick_generation();
+        glib::timeout_add_local(
+            Duration::from_millis(interval),
+            clone!(
+                #[strong]
+                program,
+                #[strong]
+                drawing,
+                move || {
+                    let state = program.state.borrow();
+                    if state.tick_interval().is_none() || state.tick_generation() != generation {
+                        return glib::ControlFlow::Break;
+                    }
+                    drop(state);
+                    program.on_event(AppEvents::Tick);
+                    if program.state.borrow().needs_paint() {
+                        drawing.queue_draw();
+                        program.state.borrow_mut().stop_request_paint();
+                    }
+                    glib::ControlFlow::Continue
+                }
+            ),
+        );
+    }
+
     pub fn drawing_events(&self) {
         let motion = EventControllerMotion::new();
         let gesture = GestureClick::new();
+        let stylus = GestureStylus::new();
         let scroll = EventControllerScroll::new(EventControllerScrollFlags::all());
+        let keys = EventControllerKey::new();
+        // Captura: o Espaco chega antes de ativar um botao com foco.
+        keys.set_propagation_phase(gtk::PropagationPhase::Capture);
 
         let program = self.program.clone();
         let drawing = self.drawing.clone();
+        let foreground = self.foreground.clone();
 
         motion.connect_motion(clone!(
             #[strong]
             program,
             #[strong]
             drawing,
-            move |_, x, y| {
-                program.on_event(AppEvents::MouseMove(Point::new(x, y)));
+            #[strong]
+            foreground,
+            move |motion, x, y| {
+                if Self::is_pen(motion) {
+                    return;
+                }
+                let event =
+                    PointerEvent::mouse(Point::new(x, y)).with_modifiers(Self::modifiers(motion));
+                program.on_event(AppEvents::MouseMove(event));
+                Self::sync_colors(&program, &foreground);
                 if program.state.borrow().needs_paint() {
                     drawing.queue_draw();
                     program.state.borrow_mut().stop_request_paint();
@@ -233,21 +494,71 @@ impl MainWindow {
             program,
             #[strong]
             drawing,
-            move |_, _, x, y| {
-                program.on_event(AppEvents::MouseDown(Point::new(x, y)));
+            #[strong]
+            foreground,
+            move |gesture, _, x, y| {
+                if Self::is_pen(gesture) {
+                    return;
+                }
+                let event =
+                    PointerEvent::mouse(Point::new(x, y)).with_modifiers(Self::modifiers(gesture));
+                Self::pointer_down(&program, &drawing, &foreground, event);
+            }
+        ));
+        gesture.connect_released(clone!(
+            #[strong]
+            program,
+            #[strong]
+            drawing,
+            move |gesture, _, x, y| {
+                if Self::is_pen(gesture) {
+                    return;
+                }
+                let event =
+                    PointerEvent::mouse(Point::new(x, y)).with_modifiers(Self::modifiers(gesture));
+                program.on_event(AppEvents::MouseUp(event));
                 if program.state.borrow().needs_paint() {
                     drawing.queue_draw();
                     program.state.borrow_mut().stop_request_paint();
                 }
             }
         ));
-        gesture.connect_released(clone!(
+
+        stylus.connect_down(clone!(
             #[strong]
             program,
             #[strong]
             drawing,
-            move |_, _, x, y| {
-                program.on_event(AppEvents::MouseUp(Point::new(x, y)));
+            #[strong]
+            foreground,
+            move |stylus, x, y| {
+                let event = Self::stylus_event(stylus, x, y);
+                Self::pointer_down(&program, &drawing, &foreground, event);
+            }
+        ));
+        stylus.connect_motion(clone!(
+            #[strong]
+            program,
+            #[strong]
+            drawing,
+            #[strong]
+            foreground,
+            move |stylus, x, y| {
+                program.on_event(AppEvents::MouseMove(Self::stylus_event(stylus, x, y)));
+                Self::sync_colors(&program, &foreground);
+                if program.state.borrow().needs_paint() {
+                    drawing.queue_draw();
+                    program.state.borrow_mut().stop_request_paint();
+                }
+            }
+        ));
+        stylus.connect_up(clone!(
+            #[strong]
+            program,
+            #[strong]
+            drawing,
+            move |stylus, x, y| {
+                program.on_event(AppEvents::MouseUp(Self::stylus_event(stylus, x, y)));
                 if program.state.borrow().needs_paint() {
                     drawing.queue_draw();
                     program.state.borrow_mut().stop_request_paint();
@@ -270,8 +581,57 @@ impl MainWindow {
             }
         ));
 
+        // Teclas chegam pela janela, ja que a area de desenho nao recebe foco.
+        keys.connect_key_pressed(clone!(
+            #[strong]
+            program,
+            #[strong]
+            drawing,
+            move |_, keyval, _, state| {
+                program.on_event(AppEvents::KeyDown(Self::key_event(keyval, state, true)));
+                if program.state.borrow().needs_paint() {
+                    drawing.queue_draw();
+                    program.state.borrow_mut().stop_request_paint();
+                }
+                // Espaco e Alt usados como troca de ferramenta nao ativam botoes.
+                if program.state.borrow_mut().take_key_consumed() {
+                    glib::Propagation::Stop
+                } else {
+                    glib::Propagation::Proceed
+                }
+            }
+        ));
+        keys.connect_key_released(clone!(
+            #[strong]
+            program,
+            #[strong]
+            drawing,
+            move |_, keyval, _, state| {
+                program.on_event(AppEvents::KeyUp(Self::key_event(keyval, state, false)));
+                if program.state.borrow().needs_paint() {
+                    drawing.queue_draw();
+                    program.state.borrow_mut().stop_request_paint();
+                }
+            }
+        ));
+
+        let focus = EventControllerFocus::new();
+        focus.connect_leave(clone!(
+            #[strong]
+            program,
+            #[strong]
+            drawing,
+            move |_| {
+                program.release_keys();
+                drawing.queue_draw();
+            }
+        ));
+
+        self.window.add_controller(keys);
+        self.window.add_controller(focus);
         drawing.add_controller(motion);
         drawing.add_controller(gesture);
+        drawing.add_controller(stylus);
         drawing.add_controller(scroll);
         drawing.set_draw_func(clone!(
             #[strong]
@@ -282,6 +642,86 @@ impl MainWindow {
         ));
     }
 
+    // Mouse e caneta passam pelos mesmos modificadores (Ctrl = origem do carimbo).
+    fn pointer_down(
+        program: &Rc<Program>,
+        drawing: &Rc<DrawingArea>,
+        foreground: &ColorDialogButton,
+        event: PointerEvent,
+    ) {
+        if event.modifiers.ctrl && program.set_clone_source(event.point) {
+            return;
+        }
+        program.on_event(AppEvents::MouseDown(event));
+        Self::sync_colors(program, foreground);
+        if program.state.borrow().needs_paint() {
+            drawing.queue_draw();
+            program.state.borrow_mut().stop_request_paint();
+        }
+        Self::start_ticks(program, drawing);
+    }
+
+    // Eventos da caneta chegam pelo GestureStylus, com pressao e inclinacao.
+    fn is_pen(controller: &impl IsA<EventController>) -> bool {
+        controller
+            .current_event_device()
+            .is_some_and(|device| device.source() == gdk::InputSource::Pen)
+    }
+
+    fn stylus_event(stylus: &GestureStylus, x: f64, y: f64) -> PointerEvent {
+        let device = match stylus.device_tool().map(|tool| tool.tool_type()) {
+            Some(gdk::DeviceToolType::Eraser) => DeviceTool::Eraser,
+            _ => DeviceTool::Pen,
+        };
+        PointerEvent::stylus(
+            Point::new(x, y),
+            stylus.axis(gdk::AxisUse::Pressure).unwrap_or(1.0),
+            stylus.axis(gdk::AxisUse::Xtilt).unwrap_or(0.0),
+            stylus.axis(gdk::AxisUse::Ytilt).unwrap_or(0.0),
+            device,
+        )
+        .with_modifiers(Self::modifiers(stylus))
+    }
+
+    fn modifiers(controller: &impl IsA<EventController>) -> Modifiers {
+        Self::modifier_state(controller.current_event_state())
+    }
+
+    fn modifier_state(state: gdk::ModifierType) -> Modifiers {
+        Modifiers {
+            shift: state.contains(gdk::ModifierType::SHIFT_MASK),
+            ctrl: state.contains(gdk::ModifierType::CONTROL_MASK),
+            alt: state.contains(gdk::ModifierType::ALT_MASK),
+            super_key: state.contains(gdk::ModifierType::SUPER_MASK),
+        }
+    }
+
+    // O GDK informa o estado anterior a tecla; aqui ele ja inclui a tecla.
+    fn key_event(keyval: gdk::Key, state: gdk::ModifierType, pressed: bool) -> KeyEvent {
+        let key = match keyval {
+            gdk::Key::space => Key::Space,
+            gdk::Key::Shift_L | gdk::Key::Shift_R => Key::Shift,
+            gdk::Key::Control_L | gdk::Key::Control_R => Key::Ctrl,
+            gdk::Key::Alt_L | gdk::Key::Alt_R => Key::Alt,
+            gdk::Key::Super_L | gdk::Key::Super_R => Key::Super,
+            gdk::Key::Escape => Key::Escape,
+            gdk::Key::Return | gdk::Key::KP_Enter => Key::Enter,
+            _ => match keyval.to_unicode() {
+                Some(c) if !c.is_control() => Key::Char(c.to_ascii_lowercase()),
+                _ => Key::Other(keyval.into_glib()),
+            },
+        };
+        let mut modifiers = Self::modifier_state(state);
+        match key {
+            Key::Shift => modifiers.shift = pressed,
+            Key::Ctrl => modifiers.ctrl = pressed,
+            Key::Alt => modifiers.alt = pressed,
+            Key::Super => modifiers.super_key = pressed,
+            _ => {}
+        }
+        KeyEvent { key, modifiers }
+    }
+
     pub fn register_zoom_action(&self) {
         self.on_register_action(
             actions::ZOOM_IN,
@@ -354,14 +794,15 @@ impl MainWindow {
 
             },
         );
+        // Espaco so troca temporariamente (ver App::spring_load), sem atalho aqui.
         self.on_register_action(
             actions::TOOL_PAN,
-            &["space"],
+            &[],
             clone!(
                 #[strong]
                 tool_action,
                 move |_, _| {
-                    tool_action.change_state(&actions::TOOL_PAN.to_variant());
+                    tool_action.change_state(&Tools::Pan.as_str().to_variant());
                 }
             ),
         );
@@ -372,10 +813,49 @@ impl MainWindow {
                 #[strong]
                 tool_action,
                 move |_, _| {
-                    tool_action.change_state(&actions::TOOL_BRUSH.to_variant());
+                    tool_action.change_state(&Tools::Brush.as_str().to_variant());
                 }
             ),
         );
+
+        let program = self.program.clone();
+        let aligned = SimpleAction::new_stateful(actions::CLONE_ALIGNED, None, &true.to_variant());
+        aligned.connect_change_state(move |action, value| {
+            if let Some(aligned) = value.and_then(|v| v.get::<bool>()) {
+                program.set_clone_aligned(aligned);
+                action.set_state(&aligned.to_variant());
+            }
+        });
+        self.gtk_app.add_action(&aligned);
+    }
+
+    fn register_filter_action(&self) {
+        self.on_register_filter_action(actions::FILTER, filters::create);
+        self.on_register_filter_action(actions::ADJUSTMENT, adjustments::create);
+    }
+
+    fn on_register_filter_action(&self, name: &str, create: fn(&str) -> Option<Box<dyn Filter>>) {
+        let window = self.window();
+        let action = SimpleAction::new(name, Some(&VariantType::new("s").unwrap()));
+        action.connect_activate(clone!(
+            #[strong(rename_to = program)]
+            self.program,
+            #[strong(rename_to = drawing)]
+            self.drawing,
+            move |_, value| {
+                let Some(id) = value.and_then(|v| v.get::<String>()) else {
+                    return;
+                };
+                match create(&id) {
+                    Some(filter) => {
+                        FilterDialog::new(&window, program.clone(), drawing.clone(), filter).show()
+                    }
+                    None => eprintln!("Filtro desconhecido: {}", id),
+                }
+            }
+        ));
+
+        self.gtk_app.add_action(&action);
     }
 
     pub fn on_register_action<F: Fn(Rc<Program>, Rc<DrawingArea>) + 'static>(
@@ -1,4 +1,4 @@
-use crate::gtk_gui::actions;
+use crate::{adjustments, filters, gtk_gui::actions};
 use gtk::{
     Application,
     gio::{Menu, MenuItem},
@@ -12,6 +12,9 @@ impl MenuBar {
         let menu_bar = Menu::new();
 
         menu_bar.append_submenu(Some("File"), &Self::menu_file());
+        menu_bar.append_submenu(Some("Tools"), &Self::menu_tools());
+        menu_bar.append_submenu(Some("Colors"), &Self::menu_colors());
+        menu_bar.append_submenu(Some("Filters"), &Self::menu_filters());
 
         app.set_menubar(Some(&menu_bar));
     }
@@ -19,11 +22,65 @@ impl MenuBar {
     fn menu_file() -> Menu {
         let file = Menu::new();
         let open_file = MenuItem::new(Some("Open"), Some(actions::app::OPEN_IMAGE));
+        let import_brushes =
+            MenuItem::new(Some("Import Brushes…"), Some(actions::app::IMPORT_BRUSHES));
+        let save_preset = MenuItem::new(
+            Some("Save Brush Preset…"),
+            Some(actions::app::SAVE_BRUSH_PRESET),
+        );
+        let export_presets = MenuItem::new(
+            Some("Export Brush Bundle…"),
+            Some(actions::app::EXPORT_BRUSH_PRESETS),
+        );
         let exit = MenuItem::new(Some("Exit"), Some(actions::app::EXIT));
 
         file.append_item(&open_file);
+        file.append_item(&import_brushes);
+        file.append_item(&save_preset);
+        file.append_item(&export_presets);
         file.append_item(&exit);
 
         file
     }
+
+    fn menu_tools() -> Menu {
+        let tools = Menu::new();
+        let clone_aligned = MenuItem::new(Some("Aligned Clone"), Some(actions::app::CLONE_ALIGNED));
+
+        let paper = MenuItem::new(
+            Some("Load Paper Texture…"),
+            Some(actions::app::LOAD_PAPER_TEXTURE),
+        );
+
+        let define_brush = MenuItem::new(
+            Some("Define Brush from Selection"),
+            Some(actions::app::DEFINE_BRUSH),
+        );
+
+        tools.append_item(&clone_aligned);
+        tools.append_item(&paper);
+        tools.append_item(&define_brush);
+
+        tools
+    }
+
+    fn menu_colors() -> Menu {
+        let menu = Menu::new();
+        for adjustment in adjustments::all() {
+            let action = format!("{}::{}", actions::app::ADJUSTMENT, adjustment.id());
+            menu.append_item(&MenuItem::new(Some(adjustment.name()), Some(&action)));
+        }
+
+        menu
+    }
+
+    fn menu_filters() -> Menu {
+        let menu = Menu::new();
+        for filter in filters::all() {
+            let action = format!("{}::{}", actions::app::FILTER, filter.id());
+            menu.append_item(&MenuItem::new(Some(filter.name()), Some(&action)));
+        }
+
+        menu
+    }
 }
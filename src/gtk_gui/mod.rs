@@ -1,4 +1,8 @@
 pub mod actions;
+mod brush_name_dialog;
+mod brush_panel;
+mod curve_editor;
+mod filter_dialog;
 mod main_window;
 mod menu_bar;
 mod open_image;
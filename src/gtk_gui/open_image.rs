@@ -22,6 +22,48 @@ impl OpenImage {
         OpenImage { dialog }
     }
 
+    pub fn brushes() -> Self {
+        let filters = FileFilter::new();
+        filters.set_name(Some("Brushes (mpbrush, mpbrushes, gbr, gih, abr, myb)"));
+        for pattern in [
+            "*.mpbrush",
+            "*.mpbrushes",
+            "*.gbr",
+            "*.gih",
+            "*.abr",
+            "*.myb",
+        ] {
+            filters.add_pattern(pattern);
+        }
+
+        let dialog = FileDialog::builder()
+            .default_filter(&filters)
+            .title("Import Brushes")
+            .modal(true)
+            .build();
+
+        OpenImage { dialog }
+    }
+
+    pub fn brush_presets(title: &str, initial_name: &str) -> Self {
+        let dialog = FileDialog::builder()
+            .title(title)
+            .initial_name(initial_name)
+            .modal(true)
+            .build();
+
+        OpenImage { dialog }
+    }
+
+    pub fn run_save<F: Fn(Option<PathBuf>) + 'static>(&self, window: &ApplicationWindow, f: F) {
+        self.dialog
+            .save(Some(window), None::<&Cancellable>, move |result| {
+                if let Ok(file) = result {
+                    f(file.path());
+                }
+            });
+    }
+
     pub fn run<F: Fn(Option<PathBuf>) + 'static>(&self, window: &ApplicationWindow, f: F) {
         self.dialog
             .open(Some(window), None::<&Cancellable>, move |result| {
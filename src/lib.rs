@@ -1,4 +1,6 @@
+mod adjustments;
 pub mod core;
+mod filters;
 mod geometry;
 mod program;
 mod tools;
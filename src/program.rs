@@ -1,10 +1,27 @@
-use crate::core::{app::{App, Tools}, event::AppEvents};
-use gtk::{cairo::Context, gdk_pixbuf::Pixbuf};
-use std::cell::RefCell;
+use crate::{
+    core::{app::{App, Tools}, color::Color, event::AppEvents},
+    filters::{Filter, FilterParam},
+    geometry::{Curve, Point},
+};
+use gtk::{
+    cairo::{Context, ImageSurface},
+    gdk_pixbuf::Pixbuf,
+};
+use std::{cell::RefCell, path::Path, rc::Rc};
 
 #[derive(Default)]
 pub struct ProgramState {
     needs_paint: bool,
+    // Intervalo em ms do AppEvents::Tick pedido pela ferramenta ativa.
+    tick_interval: Option<u64>,
+    // Muda a cada pedido, para o timer de um traco anterior parar.
+    tick_generation: u64,
+    // A cor de frente mudou fora da interface (ex.: conta-gotas).
+    colors_changed: bool,
+    // O cache de variantes dos pinceis mudou (fim de um traco).
+    cache_changed: bool,
+    // A tecla trocou de ferramenta e nao deve chegar aos outros widgets.
+    key_consumed: bool,
 }
 
 impl ProgramState {
@@ -19,11 +36,56 @@ impl ProgramState {
     pub fn needs_paint(&self) -> bool {
         self.needs_paint
     }
+
+    pub fn request_ticks(&mut self, interval_ms: u64) {
+        self.tick_interval = Some(interval_ms.max(1));
+        self.tick_generation += 1;
+    }
+
+    pub fn stop_ticks(&mut self) {
+        self.tick_interval = None;
+    }
+
+    pub fn tick_interval(&self) -> Option<u64> {
+        self.tick_interval
+    }
+
+    pub fn tick_generation(&self) -> u64 {
+        self.tick_generation
+    }
+
+    pub fn request_color_sync(&mut self) {
+        self.colors_changed = true;
+    }
+
+    pub fn take_color_sync(&mut self) -> bool {
+        std::mem::take(&mut self.colors_changed)
+    }
+
+    pub fn request_cache_sync(&mut self) {
+        self.cache_changed = true;
+    }
+
+    pub fn take_cache_sync(&mut self) -> bool {
+        std::mem::take(&mut self.cache_changed)
+    }
+
+    pub fn consume_key(&mut self) {
+        self.key_consumed = true;
+    }
+
+    pub fn take_key_consumed(&mut self) -> bool {
+        std::mem::take(&mut self.key_consumed)
+    }
 }
 
 pub struct Program {
     app: RefCell<App>,
     pub state: RefCell<ProgramState>,
+    // Avisados quando a lista de pinceis muda.
+    brushes_listeners: RefCell<Vec<Rc<dyn Fn()>>>,
+    // Avisados quando as estatisticas do cache mudam.
+    cache_listeners: RefCell<Vec<Rc<dyn Fn()>>>,
 }
 
 impl Program {
@@ -31,6 +93,29 @@ impl Program {
         Program {
             app: RefCell::new(App::new()),
             state: RefCell::default(),
+            brushes_listeners: RefCell::default(),
+            cache_listeners: RefCell::default(),
+        }
+    }
+
+    pub fn connect_brushes_changed(&self, f: impl Fn() + 'static) {
+        self.brushes_listeners.borrow_mut().push(Rc::new(f));
+    }
+
+    pub fn connect_cache_changed(&self, f: impl Fn() + 'static) {
+        self.cache_listeners.borrow_mut().push(Rc::new(f));
+    }
+
+    // Chama `f` quando algum arquivo da biblioteca muda no disco.
+    pub fn connect_library_changed(&self, f: impl Fn() + 'static) {
+        self.app.borrow().connect_library_changed(f);
+    }
+
+    // Os ouvintes podem consultar o programa, entao nada fica emprestado aqui.
+    fn notify(listeners: &RefCell<Vec<Rc<dyn Fn()>>>) {
+        let listeners = listeners.borrow().clone();
+        for listener in listeners {
+            listener();
         }
     }
 
@@ -38,6 +123,96 @@ impl Program {
         self.app.borrow_mut().set_tool(tool);
     }
     
+    pub fn foreground(&self) -> Color {
+        self.app.borrow().foreground()
+    }
+
+    pub fn set_clone_source(&self, point: Point) -> bool {
+        self.app.borrow_mut().set_clone_source(point)
+    }
+
+    pub fn set_clone_aligned(&self, aligned: bool) {
+        self.app.borrow_mut().set_clone_aligned(aligned);
+    }
+
+    pub fn pressure_curve(&self, index: usize) -> Option<Curve> {
+        self.app.borrow().pressure_curve(index)
+    }
+
+    pub fn set_pressure_curve(&self, index: usize, curve: Curve) {
+        self.app.borrow_mut().set_pressure_curve(index, curve);
+    }
+
+    pub fn set_foreground_color(&self, r: f64, g: f64, b: f64) {
+        self.app.borrow_mut().set_foreground(Color::new(r, g, b));
+    }
+
+    pub fn set_background_color(&self, r: f64, g: f64, b: f64) {
+        self.app.borrow_mut().set_background(Color::new(r, g, b));
+    }
+
+    pub fn brush_dynamics_params(&self) -> Vec<FilterParam> {
+        self.app.borrow().brush_dynamics_params()
+    }
+
+    pub fn set_brush_dynamics_param(&self, key: &str, value: f64) {
+        self.app.borrow_mut().set_brush_dynamics_param(key, value);
+    }
+
+    pub fn brush_params(&self) -> Vec<FilterParam> {
+        self.app.borrow().brush_params()
+    }
+
+    pub fn set_brush_param(&self, key: &str, value: f64) {
+        self.app.borrow_mut().set_brush_param(key, value);
+        // A guia de simetria pode ter mudado.
+        self.state.borrow_mut().request_paint();
+    }
+
+    pub fn brush_blend_modes(&self) -> Vec<&'static str> {
+        self.app.borrow().brush_blend_modes()
+    }
+
+    pub fn brush_blend_mode(&self) -> usize {
+        self.app.borrow().brush_blend_mode()
+    }
+
+    pub fn set_brush_blend_mode(&self, index: usize) {
+        self.app.borrow_mut().set_brush_blend_mode(index);
+    }
+
+    pub fn dual_brush(&self) -> Option<String> {
+        self.app.borrow().dual_brush()
+    }
+
+    pub fn set_dual_brush(&self, name: Option<String>) {
+        self.app.borrow_mut().set_dual_brush(name);
+    }
+
+    pub fn brush_names(&self) -> (Vec<String>, String) {
+        self.app.borrow().brush_names()
+    }
+
+    pub fn brush_thumbnail(&self, name: &str) -> Option<ImageSurface> {
+        self.app.borrow_mut().brush_thumbnail(name)
+    }
+
+    pub fn brush_cache_stats(&self) -> String {
+        self.app.borrow().brush_cache_stats()
+    }
+
+    pub fn select_brush(&self, name: &str) {
+        self.app.borrow_mut().select_brush(name);
+    }
+
+    // Avisa os ouvintes quando a pasta de pinceis mudou desde a ultima chamada.
+    pub fn reload_brushes(&self) {
+        let changed = self.app.borrow_mut().reload_brushes();
+        if changed {
+            Self::notify(&self.brushes_listeners);
+        }
+    }
+
     pub fn zoom_in(&self) {
         self.app.borrow_mut().zoom_in();
     }
@@ -64,19 +239,162 @@ impl Program {
         format!("{:.1}%", rotate)
     }
     pub fn open_image(&self, path: impl Into<String>) {
-        let pixbuf = Pixbuf::from_file(path.into());
+        let path = path.into();
+        let pixbuf = Pixbuf::from_file(&path);
         if let Ok(image) = pixbuf {
-            self.app.borrow_mut().open(image);
+            self.app.borrow_mut().open(image, &path);
+        }
+    }
+
+    pub fn import_brushes(&self, path: &Path) {
+        let result = self.app.borrow_mut().import_brushes(path);
+        match result {
+            Ok(_) => Self::notify(&self.brushes_listeners),
+            Err(e) => eprintln!("Erro ao importar pinceis {}: {}", path.display(), e),
+        }
+    }
+
+    pub fn has_selection(&self) -> bool {
+        self.app.borrow().has_selection()
+    }
+
+    pub fn define_brush(&self, name: &str) {
+        let result = self.app.borrow_mut().define_brush(name);
+        match result {
+            Ok(_) => Self::notify(&self.brushes_listeners),
+            Err(e) => eprintln!("Erro ao definir o pincel: {}", e),
+        }
+    }
+
+    pub fn save_brush_preset(&self, path: &Path) {
+        if let Err(e) = self.app.borrow_mut().save_brush_preset(path) {
+            eprintln!("Erro ao salvar o preset {}: {}", path.display(), e);
+        }
+    }
+
+    pub fn export_brush_presets(&self, path: &Path) {
+        if let Err(e) = self.app.borrow_mut().export_brush_presets(path) {
+            eprintln!("Erro ao exportar os presets {}: {}", path.display(), e);
+        }
+    }
+
+    pub fn load_paper_texture(&self, path: &Path) {
+        if let Err(e) = self.app.borrow_mut().load_paper_texture(path) {
+            eprintln!("Erro ao carregar o papel {}: {}", path.display(), e);
         }
     }
 
+    pub fn preview_filter(&self, filter: &dyn Filter) {
+        self.app.borrow_mut().preview_filter(filter);
+    }
+
+    pub fn cancel_filter_preview(&self) {
+        self.app.borrow_mut().cancel_filter_preview();
+    }
+
+    pub fn apply_filter(&self, filter: &dyn Filter) {
+        self.app.borrow_mut().apply_filter(filter);
+    }
+
     pub fn on_event(&self, events: AppEvents) {
         self.app
             .borrow_mut()
             .on_event(events, &mut self.state.borrow_mut());
+        let cache_changed = self.state.borrow_mut().take_cache_sync();
+        if cache_changed {
+            Self::notify(&self.cache_listeners);
+        }
+    }
+
+    pub fn release_keys(&self) {
+        self.app
+            .borrow_mut()
+            .release_keys(&mut self.state.borrow_mut());
     }
 
     pub fn draw(&self, ctx: &Context) {
         self.app.borrow_mut().draw(ctx);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        event::{DeviceTool, PointerEvent},
+        pixel_buffer::PixelBuffer,
+    };
+    use gtk::{cairo::Format, gdk_pixbuf::Colorspace};
+
+    const SIZE: i32 = 200;
+
+    // Imagem transparente; sem desenhar antes, a tela coincide com a imagem.
+    fn program() -> Program {
+        let program = Program::new();
+        let pixbuf = Pixbuf::new(Colorspace::Rgb, true, 8, SIZE, SIZE).unwrap();
+        pixbuf.fill(0);
+        program.app.borrow_mut().open(pixbuf, "test.png");
+        program.set_tool(Tools::Brush);
+        program.set_brush_dynamics_param("angle-mode", 0.0);
+        program
+    }
+
+    // Traco de caneta com pressao constante pelos pontos dados.
+    fn stroke(program: &Program, points: &[(f64, f64)], pressure: f64) {
+        let pen = |(x, y): (f64, f64)| {
+            PointerEvent::stylus(Point::new(x, y), pressure, 0.0, 0.0, DeviceTool::Pen)
+        };
+        program.on_event(AppEvents::MouseDown(pen(points[0])));
+        for point in &points[1..] {
+            program.on_event(AppEvents::MouseMove(pen(*point)));
+        }
+        program.on_event(AppEvents::MouseUp(pen(points[points.len() - 1])));
+    }
+
+    fn render(program: &Program) -> PixelBuffer {
+        let surface = ImageSurface::create(Format::ARgb32, SIZE, SIZE).unwrap();
+        let ctx = Context::new(&surface).unwrap();
+        ctx.translate(SIZE as f64 / 2.0, SIZE as f64 / 2.0);
+        program.draw(&ctx);
+        drop(ctx);
+        PixelBuffer::from_image(&surface).unwrap()
+    }
+
+    fn coverage(pixels: &PixelBuffer) -> usize {
+        pixels.to_rgba_f32().iter().filter(|px| px[3] > 0.0).count()
+    }
+
+    #[test]
+    fn pressure_widens_stroke() {
+        let line = |pressure| {
+            let program = program();
+            let points: Vec<_> = (0..=16).map(|i| (20.0 + i as f64 * 10.0, 100.0)).collect();
+            stroke(&program, &points, pressure);
+            coverage(&render(&program))
+        };
+        assert!(line(1.0) > line(0.2));
+    }
+
+    #[test]
+    fn pressure_darkens_dab() {
+        let alpha = |pressure| {
+            let program = program();
+            stroke(&program, &[(100.0, 100.0)], pressure);
+            render(&program).get_premultiplied(100, 100)[3]
+        };
+        assert!(alpha(1.0) > alpha(0.2));
+    }
+
+    #[test]
+    fn flat_curves_ignore_pressure() {
+        let flat = || Curve::new(vec![(0.0, 1.0), (1.0, 1.0)]);
+        let line = |pressure| {
+            let program = program();
+            program.set_pressure_curve(0, flat());
+            program.set_pressure_curve(1, flat());
+            stroke(&program, &[(50.0, 50.0), (150.0, 150.0)], pressure);
+            coverage(&render(&program))
+        };
+        assert_eq!(line(0.2), line(1.0));
+    }
+}
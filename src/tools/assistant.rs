@@ -0,0 +1,33 @@
+use crate::{
+    core::{canvas::Canvas, event::AppEvents},
+    program::ProgramState,
+};
+
+// Arrasta os pontos de controle dos assistentes (pontos de fuga, regua, elipse).
+pub struct AssistantTool {
+    dragging: Option<usize>,
+}
+
+impl AssistantTool {
+    pub fn new() -> Self {
+        AssistantTool { dragging: None }
+    }
+
+    pub fn on_event(&mut self, events: AppEvents, canvas: &mut Canvas, state: &mut ProgramState) {
+        match events {
+            AppEvents::MouseDown(event) => {
+                let point = canvas.screen_to_canvas_coords(event.point);
+                self.dragging = canvas.assistant_handle_at(point);
+            }
+            AppEvents::MouseMove(event) => {
+                if let Some(index) = self.dragging {
+                    let point = canvas.screen_to_canvas_coords(event.point);
+                    canvas.move_assistant_handle(index, point);
+                    state.request_paint();
+                }
+            }
+            AppEvents::MouseUp(_) => self.dragging = None,
+            _ => {}
+        }
+    }
+}
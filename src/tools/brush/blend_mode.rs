@@ -0,0 +1,150 @@
+use crate::core::canvas::StrokeBlend;
+use gtk::cairo::Operator;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlendMode {
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Subtract,
+    Divide,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+    Behind,
+    Erase,
+}
+
+impl BlendMode {
+    pub const ALL: [BlendMode; 20] = [
+        BlendMode::Normal,
+        BlendMode::Multiply,
+        BlendMode::Screen,
+        BlendMode::Overlay,
+        BlendMode::Darken,
+        BlendMode::Lighten,
+        BlendMode::ColorDodge,
+        BlendMode::ColorBurn,
+        BlendMode::HardLight,
+        BlendMode::SoftLight,
+        BlendMode::Difference,
+        BlendMode::Exclusion,
+        BlendMode::Subtract,
+        BlendMode::Divide,
+        BlendMode::Hue,
+        BlendMode::Saturation,
+        BlendMode::Color,
+        BlendMode::Luminosity,
+        BlendMode::Behind,
+        BlendMode::Erase,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BlendMode::Normal => "normal",
+            BlendMode::Multiply => "multiply",
+            BlendMode::Screen => "screen",
+            BlendMode::Overlay => "overlay",
+            BlendMode::Darken => "darken",
+            BlendMode::Lighten => "lighten",
+            BlendMode::ColorDodge => "color-dodge",
+            BlendMode::ColorBurn => "color-burn",
+            BlendMode::HardLight => "hard-light",
+            BlendMode::SoftLight => "soft-light",
+            BlendMode::Difference => "difference",
+            BlendMode::Exclusion => "exclusion",
+            BlendMode::Subtract => "subtract",
+            BlendMode::Divide => "divide",
+            BlendMode::Hue => "hue",
+            BlendMode::Saturation => "saturation",
+            BlendMode::Color => "color",
+            BlendMode::Luminosity => "luminosity",
+            BlendMode::Behind => "behind",
+            BlendMode::Erase => "erase",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BlendMode::Normal => "Normal",
+            BlendMode::Multiply => "Multiply",
+            BlendMode::Screen => "Screen",
+            BlendMode::Overlay => "Overlay",
+            BlendMode::Darken => "Darken",
+            BlendMode::Lighten => "Lighten",
+            BlendMode::ColorDodge => "Color Dodge",
+            BlendMode::ColorBurn => "Color Burn",
+            BlendMode::HardLight => "Hard Light",
+            BlendMode::SoftLight => "Soft Light",
+            BlendMode::Difference => "Difference",
+            BlendMode::Exclusion => "Exclusion",
+            BlendMode::Subtract => "Subtract",
+            BlendMode::Divide => "Divide",
+            BlendMode::Hue => "Hue",
+            BlendMode::Saturation => "Saturation",
+            BlendMode::Color => "Color",
+            BlendMode::Luminosity => "Luminosity",
+            BlendMode::Behind => "Behind",
+            BlendMode::Erase => "Erase",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    pub fn index(&self) -> usize {
+        Self::ALL.iter().position(|mode| mode == self).unwrap_or(0)
+    }
+
+    // Operador do Cairo quando existe; senao, compositor proprio.
+    pub fn stroke_blend(&self) -> StrokeBlend {
+        let operator = match self {
+            BlendMode::Normal => Operator::Over,
+            BlendMode::Multiply => Operator::Multiply,
+            BlendMode::Screen => Operator::Screen,
+            BlendMode::Overlay => Operator::Overlay,
+            BlendMode::Darken => Operator::Darken,
+            BlendMode::Lighten => Operator::Lighten,
+            BlendMode::ColorDodge => Operator::ColorDodge,
+            BlendMode::ColorBurn => Operator::ColorBurn,
+            BlendMode::HardLight => Operator::HardLight,
+            BlendMode::SoftLight => Operator::SoftLight,
+            BlendMode::Difference => Operator::Difference,
+            BlendMode::Exclusion => Operator::Exclusion,
+            BlendMode::Hue => Operator::HslHue,
+            BlendMode::Saturation => Operator::HslSaturation,
+            BlendMode::Color => Operator::HslColor,
+            BlendMode::Luminosity => Operator::HslLuminosity,
+            BlendMode::Behind => Operator::DestOver,
+            BlendMode::Erase => Operator::DestOut,
+            BlendMode::Subtract => return StrokeBlend::Custom(subtract),
+            BlendMode::Divide => return StrokeBlend::Custom(divide),
+        };
+        StrokeBlend::Operator(operator)
+    }
+}
+
+fn subtract(src: [f32; 3], dst: [f32; 3]) -> [f32; 3] {
+    [0, 1, 2].map(|c| (dst[c] - src[c]).max(0.0))
+}
+
+fn divide(src: [f32; 3], dst: [f32; 3]) -> [f32; 3] {
+    [0, 1, 2].map(|c| {
+        if src[c] <= 0.0 {
+            1.0
+        } else {
+            (dst[c] / src[c]).min(1.0)
+        }
+    })
+}
@@ -1,12 +1,68 @@
-use anyhow::Result;
+use super::{
+    blend_mode::BlendMode,
+    dynamics::BrushDynamics,
+    mypaint::MypaintSettings,
+    tip::{ProceduralTip, TipShape},
+    variant_cache::{CacheStats, VariantCache, VariantKey},
+};
+use crate::core::pixel_buffer::PixelBuffer;
+use anyhow::{Result, bail};
 use gtk::cairo::{Context, Format, ImageSurface};
-use image::GenericImageView;
-use std::{collections::HashMap, f64};
+use image::{DynamicImage, GenericImageView, RgbaImage};
+use rand::Rng;
+use std::{f64, rc::Rc};
+
+// Como um pincel animado (pipe) escolhe o proximo quadro.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PipeSelection {
+    Constant,
+    Incremental,
+    Random,
+    Angular,
+}
+
+impl PipeSelection {
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "incremental" => PipeSelection::Incremental,
+            "random" => PipeSelection::Random,
+            "angular" => PipeSelection::Angular,
+            _ => PipeSelection::Constant,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PipeSelection::Constant => "constant",
+            PipeSelection::Incremental => "incremental",
+            PipeSelection::Random => "random",
+            PipeSelection::Angular => "angular",
+        }
+    }
+}
+
+// Valores sugeridos pelo pincel; None mantem o ajuste atual da ferramenta.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BrushSettings {
+    pub size: Option<f64>,
+    pub spacing: Option<f64>,
+    pub opacity: Option<f64>,
+    pub flow: Option<f64>,
+    pub hardness: Option<f64>,
+    pub blend_mode: Option<BlendMode>,
+    pub airbrush_rate: Option<f64>,
+    pub dynamics: Option<BrushDynamics>,
+}
 
 pub struct BrushDefinition {
     name: String,
-    texture: ImageSurface,
-    cache: HashMap<(i32, i32), ImageSurface>,
+    settings: BrushSettings,
+    textures: Vec<ImageSurface>,
+    tip: Option<ProceduralTip>,
+    selection: PipeSelection,
+    frame: usize,
+    cache: VariantCache,
+    mypaint: Option<Rc<MypaintSettings>>,
 }
 
 impl BrushDefinition {
@@ -15,7 +71,121 @@ impl BrushDefinition {
         Self::from_image(name, img)
     }
 
-    fn from_image(name: &str, img: image::DynamicImage) -> Result<BrushDefinition> {
+    pub fn from_image(name: &str, img: DynamicImage) -> Result<BrushDefinition> {
+        Self::from_frames(name, vec![img], PipeSelection::Constant)
+    }
+
+    pub fn from_frames(
+        name: &str,
+        frames: Vec<DynamicImage>,
+        selection: PipeSelection,
+    ) -> Result<BrushDefinition> {
+        if frames.is_empty() {
+            bail!("O pincel {} nao tem imagens", name);
+        }
+        let textures = frames
+            .into_iter()
+            .map(Self::image_to_surface)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(BrushDefinition {
+            name: name.into(),
+            settings: BrushSettings::default(),
+            textures,
+            tip: None,
+            selection,
+            frame: 0,
+            cache: VariantCache::new(),
+            mypaint: None,
+        })
+    }
+
+    pub fn procedural(name: &str, tip: ProceduralTip) -> BrushDefinition {
+        BrushDefinition {
+            name: name.into(),
+            settings: BrushSettings {
+                hardness: Some(tip.hardness),
+                ..BrushSettings::default()
+            },
+            textures: Vec::new(),
+            tip: Some(tip),
+            selection: PipeSelection::Constant,
+            frame: 0,
+            cache: VariantCache::new(),
+            mypaint: None,
+        }
+    }
+
+    // A ponta redonda so serve para a miniatura; o traco usa os ajustes .myb.
+    pub fn mypaint(name: &str, settings: Rc<MypaintSettings>) -> BrushDefinition {
+        let mut tip = ProceduralTip::new(TipShape::Circle);
+        tip.hardness = settings.base_value("hardness", 0.8).clamp(0.0, 1.0);
+        let mut brush = Self::procedural(name, tip);
+        brush.settings.size = Some(settings.base_value("radius_logarithmic", 2.0).exp() * 2.0);
+        brush.mypaint = Some(settings);
+        brush
+    }
+
+    // Ponta a partir de uma regiao da imagem, recortada ao conteudo. Com
+    // transparencia a tinta vem do alfa; numa regiao opaca, do escuro.
+    pub fn from_pixels(name: &str, buffer: &PixelBuffer) -> Result<BrushDefinition> {
+        let rect = buffer.rect();
+        let mut opaque = true;
+        let mut ink = Vec::with_capacity((rect.width * rect.height) as usize);
+        for y in rect.y..rect.y + rect.height {
+            for x in rect.x..rect.x + rect.width {
+                let [r, g, b, a] = buffer.get(x, y);
+                opaque &= a >= 1.0;
+                ink.push((a, 1.0 - (0.299 * r + 0.587 * g + 0.114 * b)));
+            }
+        }
+        let mask: Vec<u8> = ink
+            .iter()
+            .map(|&(a, dark)| {
+                let value = if opaque { dark } else { a };
+                (value * 255.0).round().clamp(0.0, 255.0) as u8
+            })
+            .collect();
+
+        let (mut x0, mut y0, mut x1, mut y1) = (rect.width, rect.height, -1, -1);
+        for y in 0..rect.height {
+            for x in 0..rect.width {
+                if mask[(y * rect.width + x) as usize] > 0 {
+                    x0 = x0.min(x);
+                    y0 = y0.min(y);
+                    x1 = x1.max(x);
+                    y1 = y1.max(y);
+                }
+            }
+        }
+        if x1 < x0 {
+            bail!("A regiao nao tem conteudo para o pincel");
+        }
+
+        let width = x1 - x0 + 1;
+        let height = y1 - y0 + 1;
+        let cropped: Vec<u8> = (y0..=y1)
+            .flat_map(|y| {
+                let start = (y * rect.width + x0) as usize;
+                mask[start..start + width as usize].iter().copied()
+            })
+            .collect();
+        let img = Self::mask_to_image(width as u32, height as u32, &cropped)?;
+        let mut brush = Self::from_image(name, img)?;
+        brush.settings.size = Some(width.max(height) as f64);
+        Ok(brush)
+    }
+
+    // Mascara em tons de cinza (255 = tinta) vira uma imagem so com alfa.
+    pub fn mask_to_image(width: u32, height: u32, mask: &[u8]) -> Result<DynamicImage> {
+        let pixels = mask.iter().flat_map(|&a| [0, 0, 0, a]).collect();
+        match RgbaImage::from_raw(width, height, pixels) {
+            Some(img) => Ok(DynamicImage::ImageRgba8(img)),
+            None => bail!("Mascara {}x{} com tamanho invalido", width, height),
+        }
+    }
+
+    fn image_to_surface(img: DynamicImage) -> Result<ImageSurface> {
         let rgba = img.to_rgba8();
         let (width, height) = img.dimensions();
         let stride = Format::ARgb32.stride_for_width(width)?;
@@ -54,31 +224,107 @@ impl BrushDefinition {
             stride,
         )?;
 
-        Ok(BrushDefinition {
-            name: name.into(),
-            texture: surface,
-            cache: HashMap::new(),
-        })
+        Ok(surface)
     }
 
     pub fn get_name(&self) -> String {
         self.name.clone()
     }
 
-    pub fn get_cached(&mut self, thickness: f64, angle_deg: i32) -> &ImageSurface {
-        let key = (thickness.round() as i32, angle_deg);
-        if !self.cache.contains_key(&key) {
-            if let Ok(surface) = self.generate_variant(thickness, angle_deg) {
-                self.cache.insert(key, surface);
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.into();
+    }
+
+    pub fn textures(&self) -> &[ImageSurface] {
+        &self.textures
+    }
+
+    pub fn tip(&self) -> Option<&ProceduralTip> {
+        self.tip.as_ref()
+    }
+
+    // So pontas procedurais tem dureza; a troca invalida o cache.
+    pub fn set_hardness(&mut self, hardness: f64) {
+        if let Some(tip) = self.tip.as_mut()
+            && tip.hardness != hardness
+        {
+            tip.hardness = hardness;
+            self.cache.clear();
+        }
+    }
+
+    pub fn mypaint_settings(&self) -> Option<&Rc<MypaintSettings>> {
+        self.mypaint.as_ref()
+    }
+
+    pub fn selection(&self) -> PipeSelection {
+        self.selection
+    }
+
+    pub fn settings(&self) -> &BrushSettings {
+        &self.settings
+    }
+
+    pub fn set_settings(&mut self, settings: BrushSettings) {
+        self.settings = settings;
+    }
+
+    fn next_frame(&mut self, angle_deg: i32) -> usize {
+        let count = self.textures.len();
+        self.frame = match self.selection {
+            _ if count <= 1 => 0,
+            PipeSelection::Constant => 0,
+            PipeSelection::Incremental => (self.frame + 1) % count,
+            PipeSelection::Random => rand::rng().random_range(0..count),
+            PipeSelection::Angular => {
+                (angle_deg.rem_euclid(360) as usize * count / 360).min(count - 1)
             }
+        };
+        self.frame
+    }
+
+    pub fn get_cached(&mut self, thickness: f64, angle_deg: i32) -> Result<&ImageSurface> {
+        let frame = self.next_frame(angle_deg);
+        // Pincel angular: o quadro ja representa a direcao.
+        let angle_deg = if self.selection == PipeSelection::Angular {
+            0
+        } else {
+            angle_deg
+        };
+        let key = VariantKey::new(thickness, angle_deg, frame);
+        if !self.cache.touch(&key) {
+            let surface = self.generate_variant(key.thickness(), angle_deg, frame)?;
+            self.cache.insert(key, surface);
+        }
+
+        match self.cache.get(&key) {
+            Some(surface) => Ok(surface),
+            None => bail!("Variante do pincel {} indisponivel", self.name),
         }
+    }
 
-        self.cache.get(&key).unwrap()
+    // Ponta base, sem passar pelo cache de variantes nem avancar os quadros.
+    pub fn render_tip(&self, thickness: f64) -> Result<ImageSurface> {
+        self.generate_variant(thickness, 0, 0)
     }
 
-    fn generate_variant(&self, thickness: f64, angle_deg: i32) -> Result<ImageSurface> {
-        let width = self.texture.width() as f64;
-        let height = self.texture.height() as f64;
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    fn generate_variant(
+        &self,
+        thickness: f64,
+        angle_deg: i32,
+        frame: usize,
+    ) -> Result<ImageSurface> {
+        if let Some(tip) = &self.tip {
+            return tip.render(thickness, angle_deg);
+        }
+
+        let texture = &self.textures[frame];
+        let width = texture.width() as f64;
+        let height = texture.height() as f64;
         let scale = thickness / width.max(height).max(1.0);
 
         let scaled_width = (width * scale).ceil() as i32;
@@ -95,7 +341,7 @@ impl BrushDefinition {
 
         let src_x = -(width / 2.0);
         let src_y = -(height / 2.0);
-        ctx.set_source_surface(&self.texture, src_x, src_y)?;
+        ctx.set_source_surface(texture, src_x, src_y)?;
         ctx.paint()?;
 
         Ok(surface)
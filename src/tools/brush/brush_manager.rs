@@ -1,10 +1,28 @@
-use super::brush_definition::BrushDefinition;
-use anyhow::Result;
-use std::collections::HashMap;
+use super::{
+    brush_definition::BrushDefinition,
+    import,
+    library::{self, LibraryWatcher},
+    preset,
+    tip::{ProceduralTip, TipShape},
+};
+use anyhow::{Result, bail};
+use gtk::cairo::ImageSurface;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+// Pincel embutido, usado quando o ativo some da biblioteca.
+const DEFAULT_BRUSH: &str = "Circle";
 
 pub struct BrushManager {
     pub brushes: HashMap<String, BrushDefinition>,
     pub active_brush_name: String,
+    library: Vec<String>,
+    watcher: Option<LibraryWatcher>,
+    // Miniaturas do painel, por nome; refeitas quando o pincel e trocado.
+    thumbnails: HashMap<String, ImageSurface>,
 }
 
 impl BrushManager {
@@ -12,22 +30,148 @@ impl BrushManager {
         BrushManager {
             brushes: HashMap::new(),
             active_brush_name: String::new(),
+            library: Vec::new(),
+            watcher: None,
+            thumbnails: HashMap::new(),
         }
     }
 
     pub fn load_default(&mut self) -> Result<()> {
         let circle_texture = include_bytes!("./circle-texture.png");
-        let brush_circle = BrushDefinition::from_bytes("Circle", circle_texture)?;
+        let brush_circle = BrushDefinition::from_bytes(DEFAULT_BRUSH, circle_texture)?;
 
+        self.active_brush_name = brush_circle.get_name();
         self.brushes.insert(brush_circle.get_name(), brush_circle);
 
-        if let Some(name) = self.brushes.keys().next() {
-            self.active_brush_name = name.clone();
+        let procedural = [
+            ("Hard Round", TipShape::Circle, 1.0, 1.0, 0.0),
+            ("Soft Round", TipShape::Circle, 0.0, 1.0, 0.0),
+            ("Flat", TipShape::Circle, 0.8, 0.3, 45.0),
+            ("Square", TipShape::Square, 1.0, 1.0, 0.0),
+            ("Diamond", TipShape::Diamond, 0.9, 1.0, 0.0),
+        ];
+        for (name, shape, hardness, roundness, angle) in procedural {
+            let tip = ProceduralTip {
+                shape,
+                hardness,
+                roundness,
+                angle,
+            };
+            self.brushes
+                .insert(name.into(), BrushDefinition::procedural(name, tip));
         }
 
         Ok(())
     }
 
+    // Carrega as pastas de pinceis e passa a observa-las.
+    pub fn load_library(&mut self) {
+        let dirs = library::library_dirs();
+        if let Some(user_dir) = dirs.first() {
+            let _ = fs::create_dir_all(user_dir);
+        }
+        self.reload_library(&dirs);
+        self.watcher = Some(LibraryWatcher::new(&dirs));
+    }
+
+    pub fn connect_library_changed(&self, f: impl Fn() + 'static) {
+        if let Some(watcher) = self.watcher.as_ref() {
+            watcher.connect_changed(f);
+        }
+    }
+
+    // Recarrega se algum arquivo mudou; retorna true quando recarregou, mesmo
+    // que a lista de nomes seja a mesma (um arquivo pode ter sido editado).
+    pub fn reload_if_changed(&mut self) -> bool {
+        if !self.watcher.as_ref().is_some_and(|w| w.take_changed()) {
+            return false;
+        }
+        self.reload_library(&library::library_dirs());
+        true
+    }
+
+    fn reload_library(&mut self, dirs: &[PathBuf]) {
+        for name in self.library.drain(..) {
+            self.thumbnails.remove(&name);
+            self.brushes.remove(&name);
+        }
+        for dir in dirs {
+            for brush in library::load_dir(dir) {
+                // Pinceis embutidos, importados ou definidos tem prioridade.
+                let name = brush.get_name();
+                if self.brushes.contains_key(&name) {
+                    eprintln!("Pincel {} ignorado: o nome ja esta em uso", name);
+                    continue;
+                }
+                self.library.push(name.clone());
+                self.brushes.insert(name, brush);
+            }
+        }
+
+        if !self.brushes.contains_key(&self.active_brush_name) {
+            self.active_brush_name = DEFAULT_BRUSH.into();
+        }
+    }
+
+    // Importa .gbr/.gih/.abr e ativa o primeiro pincel do arquivo.
+    pub fn import_file(&mut self, path: &Path) -> Result<usize> {
+        let brushes = if preset::is_preset(path) {
+            preset::load_file(path)?
+        } else {
+            import::load_file(path)?
+        };
+        let count = brushes.len();
+        for (i, brush) in brushes.into_iter().enumerate() {
+            let name = brush.get_name();
+            if i == 0 {
+                self.active_brush_name = name.clone();
+            }
+            // Substitui um pincel da biblioteca, que nao deve mais apagar este.
+            self.library.retain(|n| *n != name);
+            self.thumbnails.remove(&name);
+            self.brushes.insert(name, brush);
+        }
+        Ok(count)
+    }
+
+    // Registra e ativa um pincel novo; repete o nome com um numero se ja existir.
+    pub fn add_brush(&mut self, mut brush: BrushDefinition) -> String {
+        let base = brush.get_name();
+        let mut name = base.clone();
+        let mut n = 2;
+        while self.brushes.contains_key(&name) {
+            name = format!("{} {}", base, n);
+            n += 1;
+        }
+        brush.set_name(&name);
+        self.thumbnails.remove(&name);
+        self.brushes.insert(name.clone(), brush);
+        self.active_brush_name = name.clone();
+        name
+    }
+
+    pub fn thumbnail(&mut self, name: &str) -> Option<ImageSurface> {
+        if let Some(thumbnail) = self.thumbnails.get(name) {
+            return Some(thumbnail.clone());
+        }
+        let thumbnail = preset::thumbnail(self.brushes.get(name)?).ok()?;
+        self.thumbnails.insert(name.to_string(), thumbnail.clone());
+        Some(thumbnail)
+    }
+
+    pub fn save_active(&self, path: &Path) -> Result<()> {
+        match self.brushes.get(&self.active_brush_name) {
+            Some(brush) => preset::save_file(path, &[brush]),
+            None => bail!("Nenhum pincel ativo"),
+        }
+    }
+
+    pub fn save_bundle(&self, path: &Path) -> Result<()> {
+        let mut brushes: Vec<&BrushDefinition> = self.brushes.values().collect();
+        brushes.sort_by_key(|b| b.get_name());
+        preset::save_file(path, &brushes)
+    }
+
     pub fn get_active_brush_mut(&mut self) -> Option<&mut BrushDefinition> {
         self.brushes.get_mut(&self.active_brush_name)
     }
@@ -0,0 +1,170 @@
+use super::brush_manager::BrushManager;
+use crate::filters::FilterParam;
+use anyhow::Result;
+use gtk::cairo::{Context, Format, ImageSurface};
+
+// Quantos angulos distintos a textura usa, para limitar o cache de pontas
+const ANGLE_STEPS: u64 = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DualMode {
+    Multiply,
+    Subtract,
+    Darken,
+}
+
+impl DualMode {
+    fn from_index(index: f64) -> Self {
+        match index.round() as i32 {
+            1 => DualMode::Subtract,
+            2 => DualMode::Darken,
+            _ => DualMode::Multiply,
+        }
+    }
+
+    fn index(&self) -> f64 {
+        match self {
+            DualMode::Multiply => 0.0,
+            DualMode::Subtract => 1.0,
+            DualMode::Darken => 2.0,
+        }
+    }
+
+    fn combine(&self, primary: u8, secondary: u8) -> u8 {
+        match self {
+            DualMode::Multiply => ((primary as u16 * secondary as u16 + 127) / 255) as u8,
+            DualMode::Subtract => primary.saturating_sub(secondary),
+            DualMode::Darken => primary.min(secondary),
+        }
+    }
+}
+
+// Segunda ponta que recorta os carimbos da principal.
+pub struct DualBrush {
+    pub brush_name: Option<String>,
+    pub mode: DualMode,
+    pub size: f64,
+    // Espacamento e dispersao relativos ao tamanho da segunda ponta.
+    pub spacing: f64,
+    pub scatter: f64,
+}
+
+impl DualBrush {
+    pub fn new() -> Self {
+        DualBrush {
+            brush_name: None,
+            mode: DualMode::Multiply,
+            size: 10.0,
+            spacing: 0.5,
+            scatter: 0.5,
+        }
+    }
+
+    pub fn params(&self) -> Vec<FilterParam> {
+        vec![
+            FilterParam::choice(
+                "dual-mode",
+                "Mode",
+                &["Multiply", "Subtract", "Darken"],
+                self.mode.index() as usize,
+            )
+            .in_group("Dual brush"),
+            FilterParam::new("dual-size", "Size", 1.0, 200.0, 1.0, self.size)
+                .in_group("Dual brush"),
+            FilterParam::new("dual-spacing", "Spacing", 0.05, 2.0, 0.01, self.spacing)
+                .in_group("Dual brush"),
+            FilterParam::new("dual-scatter", "Scatter", 0.0, 2.0, 0.01, self.scatter)
+                .in_group("Dual brush"),
+        ]
+    }
+
+    // Retorna false para chaves que nao sao da segunda ponta.
+    pub fn set_param(&mut self, key: &str, value: f64) -> bool {
+        match key {
+            "dual-mode" => self.mode = DualMode::from_index(value),
+            "dual-size" => self.size = value.max(1.0),
+            "dual-spacing" => self.spacing = value.max(0.01),
+            "dual-scatter" => self.scatter = value.max(0.0),
+            _ => return false,
+        }
+        true
+    }
+
+    // Mascara final do carimbo em (x, y); None quando nao ha segunda ponta.
+    pub fn mask(
+        &self,
+        primary: &ImageSurface,
+        x: f64,
+        y: f64,
+        brushes: &mut BrushManager,
+    ) -> Result<Option<ImageSurface>> {
+        let Some(secondary) = self
+            .brush_name
+            .as_ref()
+            .and_then(|name| brushes.brushes.get_mut(name))
+        else {
+            return Ok(None);
+        };
+
+        let width = primary.width();
+        let height = primary.height();
+        let dabs = ImageSurface::create(Format::ARgb32, width, height)?;
+        let ctx = Context::new(&dabs)?;
+        ctx.set_source_rgba(0.0, 0.0, 0.0, 1.0);
+
+        // Grade presa ao espaco da imagem; a dispersao e o angulo de cada
+        // celula vem do seu indice, entao a textura nao muda entre carimbos.
+        let step = (self.size * self.spacing).max(1.0);
+        let margin = self.size * (0.5 + self.scatter);
+        let mut row = ((y - margin) / step).floor() as i64;
+        while row as f64 * step <= y + height as f64 + margin {
+            let mut col = ((x - margin) / step).floor() as i64;
+            while col as f64 * step <= x + width as f64 + margin {
+                let hash = cell_hash(col, row);
+                let unit = |shift: u32| ((hash >> shift) & 0xffff) as f64 / 65535.0 * 2.0 - 1.0;
+                let dx = self.scatter * self.size * unit(0);
+                let dy = self.scatter * self.size * unit(16);
+                let angle = ((hash >> 32) % ANGLE_STEPS) as i32 * (360 / ANGLE_STEPS as i32);
+                let dab = secondary.get_cached(self.size, angle)?;
+                ctx.mask_surface(
+                    dab,
+                    col as f64 * step + dx - x - dab.width() as f64 / 2.0,
+                    row as f64 * step + dy - y - dab.height() as f64 / 2.0,
+                )?;
+                col += 1;
+            }
+            row += 1;
+        }
+        drop(ctx);
+
+        // As duas superficies tem o mesmo tamanho e formato, logo o mesmo stride.
+        let stride = dabs.stride();
+        let mut data = vec![0u8; (stride * height) as usize];
+        dabs.with_data(|s| data.copy_from_slice(s))?;
+        primary.with_data(|p| {
+            let p_stride = primary.stride() as usize;
+            for row in 0..height as usize {
+                for col in 0..width as usize {
+                    let i = row * stride as usize + col * 4 + 3;
+                    data[i] = self.mode.combine(p[row * p_stride + col * 4 + 3], data[i]);
+                }
+            }
+        })?;
+
+        Ok(Some(ImageSurface::create_for_data(
+            data,
+            Format::ARgb32,
+            width,
+            height,
+            stride,
+        )?))
+    }
+}
+
+// Hash estavel (splitmix64) do indice de uma celula da grade
+fn cell_hash(col: i64, row: i64) -> u64 {
+    let mut h = (col as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ (row as u64);
+    h = (h ^ (h >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    h ^ (h >> 31)
+}
@@ -0,0 +1,247 @@
+use crate::{core::color::Color, filters::FilterParam, geometry::Point};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AngleMode {
+    Fixed,
+    Random,
+    FollowStroke,
+    // Direcao para onde a caneta esta inclinada.
+    PenTilt,
+}
+
+impl AngleMode {
+    fn from_index(index: f64) -> Self {
+        match index.round() as i32 {
+            0 => AngleMode::Fixed,
+            2 => AngleMode::FollowStroke,
+            3 => AngleMode::PenTilt,
+            _ => AngleMode::Random,
+        }
+    }
+
+    fn index(&self) -> f64 {
+        match self {
+            AngleMode::Fixed => 0.0,
+            AngleMode::Random => 1.0,
+            AngleMode::FollowStroke => 2.0,
+            AngleMode::PenTilt => 3.0,
+        }
+    }
+}
+
+// Variacao aplicada a um carimbo.
+pub struct StampVariation {
+    pub size: f64,
+    pub opacity: f64,
+    pub flow: f64,
+    pub angle_deg: i32,
+    // Deslocamento relativo ao tamanho do carimbo.
+    pub offset: Point,
+    pub color: Color,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrushDynamics {
+    pub size_jitter: f64,
+    pub opacity_jitter: f64,
+    pub flow_jitter: f64,
+    pub angle_mode: AngleMode,
+    pub angle: f64,
+    pub angle_step: f64,
+    pub scatter: f64,
+    pub stamp_count: u32,
+    pub color_jitter: f64,
+    pub hue_jitter: f64,
+    pub saturation_jitter: f64,
+    pub value_jitter: f64,
+    pub seed: Option<u64>,
+}
+
+impl BrushDynamics {
+    pub fn new() -> Self {
+        BrushDynamics {
+            size_jitter: 0.0,
+            opacity_jitter: 0.0,
+            flow_jitter: 0.0,
+            angle_mode: AngleMode::Random,
+            angle: 0.0,
+            angle_step: 15.0,
+            scatter: 0.0,
+            stamp_count: 1,
+            color_jitter: 0.0,
+            hue_jitter: 0.0,
+            saturation_jitter: 0.0,
+            value_jitter: 0.0,
+            seed: None,
+        }
+    }
+
+    // Com semente, cada traco repete a mesma sequencia aleatoria.
+    pub fn stroke_rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        }
+    }
+
+    pub fn params(&self) -> Vec<FilterParam> {
+        vec![
+            FilterParam::new(
+                "size-jitter",
+                "Size jitter",
+                0.0,
+                1.0,
+                0.01,
+                self.size_jitter,
+            ),
+            FilterParam::new(
+                "opacity-jitter",
+                "Opacity jitter",
+                0.0,
+                1.0,
+                0.01,
+                self.opacity_jitter,
+            ),
+            FilterParam::new(
+                "flow-jitter",
+                "Flow jitter",
+                0.0,
+                1.0,
+                0.01,
+                self.flow_jitter,
+            ),
+            FilterParam::choice(
+                "angle-mode",
+                "Angle mode",
+                &["Fixed", "Random", "Follow stroke", "Pen tilt"],
+                self.angle_mode.index() as usize,
+            ),
+            FilterParam::new("angle", "Angle", -180.0, 180.0, 1.0, self.angle),
+            FilterParam::new(
+                "angle-step",
+                "Random angle step",
+                1.0,
+                90.0,
+                1.0,
+                self.angle_step,
+            ),
+            FilterParam::new("scatter", "Scatter", 0.0, 5.0, 0.05, self.scatter),
+            FilterParam::new(
+                "stamp-count",
+                "Stamp count",
+                1.0,
+                16.0,
+                1.0,
+                self.stamp_count as f64,
+            ),
+            FilterParam::new(
+                "color-jitter",
+                "Foreground/background jitter",
+                0.0,
+                1.0,
+                0.01,
+                self.color_jitter,
+            ),
+            FilterParam::new("hue-jitter", "Hue jitter", 0.0, 1.0, 0.01, self.hue_jitter),
+            FilterParam::new(
+                "saturation-jitter",
+                "Saturation jitter",
+                0.0,
+                1.0,
+                0.01,
+                self.saturation_jitter,
+            ),
+            FilterParam::new(
+                "value-jitter",
+                "Value jitter",
+                0.0,
+                1.0,
+                0.01,
+                self.value_jitter,
+            ),
+            FilterParam::new(
+                "seed",
+                "Seed (-1 random)",
+                -1.0,
+                65535.0,
+                1.0,
+                self.seed.map_or(-1.0, |s| s as f64),
+            ),
+        ]
+    }
+
+    pub fn set_param(&mut self, key: &str, value: f64) {
+        match key {
+            "size-jitter" => self.size_jitter = value.clamp(0.0, 1.0),
+            "opacity-jitter" => self.opacity_jitter = value.clamp(0.0, 1.0),
+            "flow-jitter" => self.flow_jitter = value.clamp(0.0, 1.0),
+            "angle-mode" => self.angle_mode = AngleMode::from_index(value),
+            "angle" => self.angle = value,
+            "angle-step" => self.angle_step = value.max(1.0),
+            "scatter" => self.scatter = value.max(0.0),
+            "stamp-count" => self.stamp_count = value.round().max(1.0) as u32,
+            "color-jitter" => self.color_jitter = value.clamp(0.0, 1.0),
+            "hue-jitter" => self.hue_jitter = value.clamp(0.0, 1.0),
+            "saturation-jitter" => self.saturation_jitter = value.clamp(0.0, 1.0),
+            "value-jitter" => self.value_jitter = value.clamp(0.0, 1.0),
+            "seed" => self.seed = (value >= 0.0).then_some(value as u64),
+            _ => {}
+        }
+    }
+
+    pub fn sample(
+        &self,
+        rng: &mut StdRng,
+        direction_deg: f64,
+        tilt_deg: f64,
+        foreground: Color,
+        background: Color,
+    ) -> StampVariation {
+        let mut jitter = |amount: f64| 1.0 - amount * rng.random::<f64>();
+        let size = jitter(self.size_jitter);
+        let opacity = jitter(self.opacity_jitter);
+        let flow = jitter(self.flow_jitter);
+
+        let angle = match self.angle_mode {
+            AngleMode::Fixed => self.angle,
+            AngleMode::FollowStroke => direction_deg + self.angle,
+            AngleMode::PenTilt => tilt_deg + self.angle,
+            AngleMode::Random => {
+                let n_steps = (360.0 / self.angle_step).floor().max(1.0) as u32;
+                (rng.random_range(0..n_steps) as f64) * self.angle_step
+            }
+        };
+
+        let offset = if self.scatter > 0.0 {
+            let radius = self.scatter * rng.random::<f64>().sqrt();
+            let theta = rng.random_range(0.0..std::f64::consts::TAU);
+            Point::new(radius * theta.cos(), radius * theta.sin())
+        } else {
+            Point::ZERO
+        };
+
+        let mut color = foreground;
+        if self.color_jitter > 0.0 {
+            color = color.mix(&background, self.color_jitter * rng.random::<f64>());
+        }
+        if self.hue_jitter > 0.0 || self.saturation_jitter > 0.0 || self.value_jitter > 0.0 {
+            let mut spread = |amount: f64| amount * rng.random_range(-1.0..=1.0);
+            let [h, s, v] = color.to_hsv();
+            color = Color::from_hsv([
+                h + spread(self.hue_jitter) * 180.0,
+                s + spread(self.saturation_jitter),
+                v + spread(self.value_jitter),
+            ]);
+        }
+
+        StampVariation {
+            size,
+            opacity,
+            flow,
+            angle_deg: angle.round().rem_euclid(360.0) as i32,
+            offset,
+            color,
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use super::reader::Reader;
+use crate::tools::brush::brush_definition::BrushDefinition;
+use anyhow::{Result, bail};
+
+// Pincel amostrado: mascara em tons de cinza.
+struct Sample {
+    name: Option<String>,
+    width: u32,
+    height: u32,
+    mask: Vec<u8>,
+}
+
+pub fn load(name: &str, data: &[u8]) -> Result<Vec<BrushDefinition>> {
+    let mut reader = Reader::new(data);
+    let version = reader.u16()?;
+    let samples = match version {
+        1 | 2 => read_v12(&mut reader, version)?,
+        6 | 7 | 10 => read_v6(&mut reader)?,
+        _ => bail!("Versao de .abr nao suportada: {}", version),
+    };
+
+    let mut brushes = Vec::new();
+    for (i, sample) in samples.into_iter().enumerate() {
+        let title = match sample.name {
+            Some(title) if !title.is_empty() => title,
+            _ => format!("{} {}", name, i + 1),
+        };
+        let image = BrushDefinition::mask_to_image(sample.width, sample.height, &sample.mask)?;
+        brushes.push(BrushDefinition::from_image(&title, image)?);
+    }
+    if brushes.is_empty() {
+        bail!("Nenhum pincel amostrado em {}", name);
+    }
+    Ok(brushes)
+}
+
+fn read_v12(reader: &mut Reader, version: u16) -> Result<Vec<Sample>> {
+    let count = reader.u16()?;
+    let mut samples = Vec::new();
+    for _ in 0..count {
+        let kind = reader.u16()?;
+        let size = reader.u32()? as usize;
+        let next = reader.position() + size;
+
+        // Tipo 1 e pincel computado, so os amostrados (2) tem pixels.
+        if kind == 2 {
+            let _misc = reader.u32()?;
+            let _spacing = reader.u16()?;
+            let name = if version == 2 {
+                Some(reader.unicode_string()?)
+            } else {
+                None
+            };
+            let _antialias = reader.u8()?;
+            reader.skip(8)?;
+            let mut sample = read_sample(reader)?;
+            sample.name = name;
+            samples.push(sample);
+        }
+        reader.seek(next)?;
+    }
+    Ok(samples)
+}
+
+fn read_v6(reader: &mut Reader) -> Result<Vec<Sample>> {
+    let subversion = reader.u16()?;
+    let header = match subversion {
+        1 => 47,
+        2 => 301,
+        _ => bail!("Subversao de .abr nao suportada: {}", subversion),
+    };
+
+    // Procura a secao "samp"; as demais sao puladas pelo tamanho.
+    while reader.remaining() >= 12 {
+        if reader.bytes(4)? != b"8BIM" {
+            bail!("Secao .abr invalida");
+        }
+        let tag = reader.bytes(4)?;
+        let size = reader.u32()? as usize;
+        let end = reader.position() + size;
+        if tag != b"samp" {
+            reader.seek(end)?;
+            continue;
+        }
+
+        let mut samples = Vec::new();
+        while reader.position() < end {
+            let len = reader.u32()? as usize;
+            let next = reader.position() + len.next_multiple_of(4);
+            reader.skip(header)?;
+            samples.push(read_sample(reader)?);
+            reader.seek(next.min(end))?;
+        }
+        return Ok(samples);
+    }
+    Ok(Vec::new())
+}
+
+// Limites (top, left, bottom, right), profundidade, compressao e pixels.
+fn read_sample(reader: &mut Reader) -> Result<Sample> {
+    let top = reader.i32()?;
+    let left = reader.i32()?;
+    let bottom = reader.i32()?;
+    let right = reader.i32()?;
+    let (Some(width), Some(height)) = (right.checked_sub(left), bottom.checked_sub(top)) else {
+        bail!("Limites invalidos: {} {} {} {}", top, left, bottom, right);
+    };
+    let width = width.max(0) as u32;
+    let height = height.max(0) as u32;
+    if width == 0 || height == 0 || width > 8192 || height > 8192 {
+        bail!("Dimensoes invalidas: {}x{}", width, height);
+    }
+
+    let depth = reader.u16()?;
+    if depth != 8 {
+        bail!("Profundidade nao suportada: {} bits", depth);
+    }
+    let compression = reader.u8()?;
+    let count = (width * height) as usize;
+    let mask = match compression {
+        0 => reader.bytes(count)?.to_vec(),
+        1 => {
+            let mut lengths = Vec::new();
+            for _ in 0..height {
+                lengths.push(reader.u16()? as usize);
+            }
+            let mut mask = Vec::new();
+            for len in lengths {
+                let row = unpack_bits(reader.bytes(len)?, width as usize);
+                mask.extend_from_slice(&row);
+            }
+            mask
+        }
+        _ => bail!("Compressao nao suportada: {}", compression),
+    };
+
+    Ok(Sample {
+        name: None,
+        width,
+        height,
+        mask,
+    })
+}
+
+// Descompressao PackBits de uma linha.
+fn unpack_bits(data: &[u8], width: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(width);
+    let mut i = 0;
+    while i < data.len() && out.len() < width {
+        let n = data[i] as i8;
+        i += 1;
+        if n >= 0 {
+            let end = (i + n as usize + 1).min(data.len());
+            out.extend_from_slice(&data[i..end]);
+            i = end;
+        } else if n != -128 {
+            if let Some(&value) = data.get(i) {
+                out.extend(std::iter::repeat_n(value, (1 - n as isize) as usize));
+            }
+            i += 1;
+        }
+    }
+    out.resize(width, 0);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Amostra sem compressao com os limites dados.
+    fn sample(top: i32, left: i32, bottom: i32, right: i32, pixels: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        for value in [top, left, bottom, right] {
+            data.extend_from_slice(&value.to_be_bytes());
+        }
+        data.extend_from_slice(&8u16.to_be_bytes());
+        data.push(0);
+        data.extend_from_slice(pixels);
+        data
+    }
+
+    // .abr versao 1 ou 2 com uma amostra.
+    fn encode_v12(version: u16, name: &str, sample: &[u8]) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u32.to_be_bytes());
+        body.extend_from_slice(&25u16.to_be_bytes());
+        if version == 2 {
+            let units: Vec<u16> = name.encode_utf16().collect();
+            body.extend_from_slice(&(units.len() as u32).to_be_bytes());
+            for unit in units {
+                body.extend_from_slice(&unit.to_be_bytes());
+            }
+        }
+        body.push(1);
+        body.extend_from_slice(&[0; 8]);
+        body.extend_from_slice(sample);
+
+        let mut data = Vec::new();
+        for value in [version, 1, 2] {
+            data.extend_from_slice(&value.to_be_bytes());
+        }
+        data.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        data.extend(body);
+        data
+    }
+
+    #[test]
+    fn reads_v1_sample() {
+        let data = encode_v12(1, "", &sample(0, 0, 2, 3, &[255; 6]));
+        let brushes = load("set", &data).unwrap();
+        assert_eq!(brushes.len(), 1);
+        assert_eq!(brushes[0].get_name(), "set 1");
+    }
+
+    #[test]
+    fn reads_v2_name() {
+        let data = encode_v12(2, "Tip", &sample(0, 0, 1, 1, &[255]));
+        assert_eq!(load("set", &data).unwrap()[0].get_name(), "Tip");
+    }
+
+    #[test]
+    fn reads_v6_sample() {
+        let sample = sample(0, 0, 1, 2, &[255, 0]);
+        let mut entry = vec![0; 47];
+        entry.extend(sample);
+        let mut section = (entry.len() as u32).to_be_bytes().to_vec();
+        section.extend(entry);
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&6u16.to_be_bytes());
+        data.extend_from_slice(&1u16.to_be_bytes());
+        data.extend_from_slice(b"8BIMsamp");
+        data.extend_from_slice(&(section.len() as u32).to_be_bytes());
+        data.extend(section);
+        assert_eq!(load("set", &data).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn rejects_bad_section_magic() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&6u16.to_be_bytes());
+        data.extend_from_slice(&1u16.to_be_bytes());
+        data.extend_from_slice(b"XXXXsamp");
+        data.extend_from_slice(&0u32.to_be_bytes());
+        assert!(load("set", &data).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        assert!(load("set", &[0, 3, 0, 0]).is_err());
+        assert!(load("set", &[0, 6, 0, 9]).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_file() {
+        let data = encode_v12(2, "Tip", &sample(0, 0, 2, 2, &[255; 4]));
+        for len in [0, 1, 5, 10, 20, data.len() - 1] {
+            assert!(load("set", &data[..len]).is_err(), "tamanho {}", len);
+        }
+    }
+
+    #[test]
+    fn rejects_oversized_and_inverted_bounds() {
+        let data = encode_v12(1, "", &sample(0, 0, 10_000, 10_000, &[]));
+        assert!(load("set", &data).is_err());
+        let data = encode_v12(1, "", &sample(0, 5, 2, 1, &[]));
+        assert!(load("set", &data).is_err());
+        let data = encode_v12(1, "", &sample(0, i32::MIN, 1, i32::MAX, &[]));
+        assert!(load("set", &data).is_err());
+    }
+
+    #[test]
+    fn rejects_oversized_name() {
+        let mut data = encode_v12(2, "Tip", &sample(0, 0, 1, 1, &[255]));
+        // Tamanho do nome logo apos misc e spacing.
+        data[16..20].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(load("set", &data).is_err());
+    }
+
+    #[test]
+    fn unpacks_runs_and_literals() {
+        assert_eq!(unpack_bits(&[0xfe, 7], 3), vec![7, 7, 7]);
+        assert_eq!(unpack_bits(&[1, 5, 6, 0xff, 9], 4), vec![5, 6, 9, 9]);
+        // Dados curtos completam com zero.
+        assert_eq!(unpack_bits(&[5, 1], 3), vec![1, 0, 0]);
+    }
+}
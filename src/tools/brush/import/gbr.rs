@@ -0,0 +1,142 @@
+use super::reader::Reader;
+use crate::tools::brush::brush_definition::BrushDefinition;
+use anyhow::{Result, bail};
+use image::{DynamicImage, RgbaImage};
+
+pub struct GbrBrush {
+    pub name: String,
+    pub image: DynamicImage,
+}
+
+// Formato: cabecalho big-endian, nome e pixels (1 byte = mascara, 4 = RGBA).
+pub fn read(reader: &mut Reader) -> Result<GbrBrush> {
+    let start = reader.position();
+    let header_size = reader.u32()? as usize;
+    let version = reader.u32()?;
+    let width = reader.u32()?;
+    let height = reader.u32()?;
+    let bytes = reader.u32()?;
+
+    let name_len = match version {
+        1 => header_size.saturating_sub(20),
+        2 | 3 => {
+            if reader.bytes(4)? != b"GIMP" {
+                bail!("Assinatura GIMP invalida");
+            }
+            let _spacing = reader.u32()?;
+            header_size.saturating_sub(28)
+        }
+        _ => bail!("Versao de .gbr nao suportada: {}", version),
+    };
+    let name = reader.c_string(name_len)?;
+    reader.seek(start + header_size)?;
+
+    if width == 0 || height == 0 || width > 8192 || height > 8192 {
+        bail!("Dimensoes invalidas: {}x{}", width, height);
+    }
+    let count = (width * height) as usize;
+    let image = match bytes {
+        1 => {
+            // No GIMP 255 e tinta cheia.
+            BrushDefinition::mask_to_image(width, height, reader.bytes(count)?)?
+        }
+        4 => {
+            let data = reader.bytes(count * 4)?.to_vec();
+            match RgbaImage::from_raw(width, height, data) {
+                Some(img) => DynamicImage::ImageRgba8(img),
+                None => bail!("Pixels invalidos"),
+            }
+        }
+        _ => bail!("Profundidade nao suportada: {} bytes", bytes),
+    };
+
+    Ok(GbrBrush { name, image })
+}
+
+pub fn load(name: &str, data: &[u8]) -> Result<Vec<BrushDefinition>> {
+    let brush = read(&mut Reader::new(data))?;
+    let name = if brush.name.is_empty() {
+        name
+    } else {
+        &brush.name
+    };
+    Ok(vec![BrushDefinition::from_image(name, brush.image)?])
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    // .gbr versao 2 com o nome e os pixels dados.
+    pub fn encode(name: &str, width: u32, height: u32, bytes: u32, pixels: &[u8]) -> Vec<u8> {
+        let header_size = 28 + name.len() as u32 + 1;
+        let mut data = Vec::new();
+        for value in [header_size, 2, width, height, bytes] {
+            data.extend_from_slice(&value.to_be_bytes());
+        }
+        data.extend_from_slice(b"GIMP");
+        data.extend_from_slice(&25u32.to_be_bytes());
+        data.extend_from_slice(name.as_bytes());
+        data.push(0);
+        data.extend_from_slice(pixels);
+        data
+    }
+
+    #[test]
+    fn reads_mask_brush() {
+        let data = encode("dot", 2, 2, 1, &[0, 255, 255, 0]);
+        let brush = read(&mut Reader::new(&data)).unwrap();
+        assert_eq!(brush.name, "dot");
+        assert_eq!((brush.image.width(), brush.image.height()), (2, 2));
+
+        let brushes = load("file", &data).unwrap();
+        assert_eq!(brushes[0].get_name(), "dot");
+    }
+
+    #[test]
+    fn reads_rgba_brush() {
+        let data = encode("", 1, 1, 4, &[10, 20, 30, 40]);
+        let brushes = load("file", &data).unwrap();
+        // Sem nome no arquivo, vale o nome do arquivo.
+        assert_eq!(brushes[0].get_name(), "file");
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut data = encode("dot", 1, 1, 1, &[255]);
+        data[20..24].copy_from_slice(b"GIMQ");
+        assert!(load("file", &data).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_header() {
+        let data = encode("dot", 1, 1, 1, &[255]);
+        for len in [0, 3, 12, 24, 30] {
+            assert!(load("file", &data[..len]).is_err(), "tamanho {}", len);
+        }
+    }
+
+    #[test]
+    fn rejects_missing_pixels() {
+        let data = encode("dot", 4, 4, 1, &[255; 15]);
+        assert!(load("file", &data).is_err());
+    }
+
+    #[test]
+    fn rejects_oversized_brush() {
+        let data = encode("big", 100_000, 100_000, 1, &[]);
+        assert!(load("file", &data).is_err());
+        let data = encode("big", u32::MAX, 2, 4, &[]);
+        assert!(load("file", &data).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_version_and_depth() {
+        let mut data = encode("dot", 1, 1, 1, &[255]);
+        data[4..8].copy_from_slice(&9u32.to_be_bytes());
+        assert!(load("file", &data).is_err());
+
+        let data = encode("dot", 1, 1, 3, &[255, 255, 255]);
+        assert!(load("file", &data).is_err());
+    }
+}
@@ -0,0 +1,68 @@
+use super::{gbr, reader::Reader};
+use crate::tools::brush::brush_definition::{BrushDefinition, PipeSelection};
+use anyhow::{Result, bail};
+
+// Formato: nome, linha de parametros ("ncells:N ... selection0:random") e N .gbr.
+pub fn load(name: &str, data: &[u8]) -> Result<Vec<BrushDefinition>> {
+    let mut reader = Reader::new(data);
+    let title = reader.line()?;
+    let params = reader.line()?;
+
+    let mut words = params.split_whitespace();
+    let count: usize = match words.next().and_then(|w| w.parse().ok()) {
+        Some(count) => count,
+        None => bail!("Cabecalho .gih invalido"),
+    };
+    let mut selection = PipeSelection::Incremental;
+    for word in words {
+        if let Some(("selection0", value)) = word.split_once(':') {
+            selection = PipeSelection::from_name(value);
+        }
+    }
+
+    let mut frames = Vec::new();
+    for _ in 0..count {
+        frames.push(gbr::read(&mut reader)?.image);
+    }
+
+    let name = if title.is_empty() { name } else { &title };
+    Ok(vec![BrushDefinition::from_frames(name, frames, selection)?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::brush::import::gbr::tests::encode;
+
+    fn encode_gih(params: &str, frames: usize) -> Vec<u8> {
+        let mut data = format!("spray\n{}\n", params).into_bytes();
+        for _ in 0..frames {
+            data.extend(encode("cell", 2, 1, 1, &[255, 128]));
+        }
+        data
+    }
+
+    #[test]
+    fn reads_frames_and_selection() {
+        let data = encode_gih("2 ncells:2 dim:1 selection0:random", 2);
+        let brushes = load("file", &data).unwrap();
+        assert_eq!(brushes.len(), 1);
+        assert_eq!(brushes[0].get_name(), "spray");
+        assert_eq!(brushes[0].textures().len(), 2);
+        assert_eq!(brushes[0].selection(), PipeSelection::Random);
+    }
+
+    #[test]
+    fn rejects_truncated_header() {
+        assert!(load("file", b"spray").is_err());
+        assert!(load("file", b"spray\n2 ncells:2").is_err());
+    }
+
+    #[test]
+    fn rejects_bad_cell_count() {
+        assert!(load("file", &encode_gih("many", 1)).is_err());
+        // Menos quadros que o anunciado.
+        assert!(load("file", &encode_gih("3 ncells:3", 2)).is_err());
+        assert!(load("file", &encode_gih("0", 0)).is_err());
+    }
+}
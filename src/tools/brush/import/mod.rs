@@ -0,0 +1,29 @@
+mod abr;
+mod gbr;
+mod gih;
+mod reader;
+
+use super::{brush_definition::BrushDefinition, mypaint};
+use anyhow::{Result, bail};
+use std::path::Path;
+
+// Carrega um arquivo de pincel do GIMP, do Photoshop ou do MyPaint.
+pub fn load_file(path: &Path) -> Result<Vec<BrushDefinition>> {
+    let data = std::fs::read(path)?;
+    let name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "gbr" => gbr::load(&name, &data),
+        "gih" => gih::load(&name, &data),
+        "abr" => abr::load(&name, &data),
+        "myb" => mypaint::load(&name, &data),
+        _ => bail!("Formato de pincel desconhecido: {}", path.display()),
+    }
+}
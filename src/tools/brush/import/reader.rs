@@ -0,0 +1,139 @@
+use anyhow::{Result, bail};
+
+// Leitor big-endian usado pelos formatos do GIMP e do Photoshop.
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Reader { data, pos: 0 }
+    }
+
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    pub fn seek(&mut self, pos: usize) -> Result<()> {
+        if pos > self.data.len() {
+            bail!("Fim inesperado do arquivo");
+        }
+        self.pos = pos;
+        Ok(())
+    }
+
+    pub fn skip(&mut self, count: usize) -> Result<()> {
+        self.seek(self.pos + count)
+    }
+
+    pub fn bytes(&mut self, count: usize) -> Result<&'a [u8]> {
+        if count > self.remaining() {
+            bail!("Fim inesperado do arquivo");
+        }
+        let slice = &self.data[self.pos..self.pos + count];
+        self.pos += count;
+        Ok(slice)
+    }
+
+    pub fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16> {
+        let b = self.bytes(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    pub fn u32(&mut self) -> Result<u32> {
+        let b = self.bytes(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub fn i32(&mut self) -> Result<i32> {
+        Ok(self.u32()? as i32)
+    }
+
+    // Texto terminado em NUL (nome dos pincéis .gbr).
+    pub fn c_string(&mut self, max: usize) -> Result<String> {
+        let raw = self.bytes(max)?;
+        let end = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+        Ok(String::from_utf8_lossy(&raw[..end]).into_owned())
+    }
+
+    // Linha de texto terminada em '\n' (cabecalho dos .gih).
+    pub fn line(&mut self) -> Result<String> {
+        let rest = &self.data[self.pos..];
+        let Some(end) = rest.iter().position(|&b| b == b'\n') else {
+            bail!("Fim inesperado do arquivo");
+        };
+        self.pos += end + 1;
+        Ok(String::from_utf8_lossy(&rest[..end]).trim().to_string())
+    }
+
+    // Texto UCS-2 com tamanho em caracteres (nomes nos .abr).
+    pub fn unicode_string(&mut self) -> Result<String> {
+        let len = self.u32()? as usize;
+        if len > self.remaining() / 2 {
+            bail!("Fim inesperado do arquivo");
+        }
+        let mut units = Vec::with_capacity(len);
+        for _ in 0..len {
+            units.push(self.u16()?);
+        }
+        let text = String::from_utf16_lossy(&units);
+        Ok(text.trim_end_matches('\0').to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_big_endian_numbers() {
+        let mut reader = Reader::new(&[0x01, 0x02, 0xff, 0xff, 0xff, 0xfe, 0x07]);
+        assert_eq!(reader.u16().unwrap(), 0x0102);
+        assert_eq!(reader.i32().unwrap(), -2);
+        assert_eq!(reader.u8().unwrap(), 7);
+        assert_eq!(reader.remaining(), 0);
+    }
+
+    #[test]
+    fn reading_past_the_end_fails() {
+        let mut reader = Reader::new(&[1, 2, 3]);
+        assert!(reader.u32().is_err());
+        assert!(reader.skip(4).is_err());
+        assert!(reader.seek(4).is_err());
+        assert!(reader.bytes(usize::MAX).is_err());
+        // Um erro nao consome bytes.
+        assert_eq!(reader.position(), 0);
+    }
+
+    #[test]
+    fn c_string_stops_at_nul() {
+        let mut reader = Reader::new(b"dot\0xx!");
+        assert_eq!(reader.c_string(6).unwrap(), "dot");
+        assert_eq!(reader.u8().unwrap(), b'!');
+    }
+
+    #[test]
+    fn line_needs_a_newline() {
+        let mut reader = Reader::new(b" name \nrest");
+        assert_eq!(reader.line().unwrap(), "name");
+        assert!(reader.line().is_err());
+    }
+
+    #[test]
+    fn unicode_string_rejects_oversized_length() {
+        let mut reader = Reader::new(&[0, 0, 0, 2, 0, b'O', 0, b'k']);
+        assert_eq!(reader.unicode_string().unwrap(), "Ok");
+
+        let mut reader = Reader::new(&[0xff, 0xff, 0xff, 0xff, 0, b'O']);
+        assert!(reader.unicode_string().is_err());
+    }
+}
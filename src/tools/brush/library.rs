@@ -0,0 +1,180 @@
+use super::{
+    brush_definition::{BrushDefinition, BrushSettings},
+    import, preset,
+};
+use anyhow::Result;
+use gtk::{
+    gio::{self, FileMonitor, FileMonitorEvent, FileMonitorFlags, prelude::FileExt},
+    glib::{self, KeyFile, KeyFileFlags, SourceId},
+    prelude::FileMonitorExt,
+};
+use std::{
+    cell::{Cell, RefCell},
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::Duration,
+};
+
+const SETTINGS_GROUP: &str = "Brush";
+
+// Pasta do usuario (XDG) e pasta "brushes" do projeto atual.
+pub fn library_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![glib::user_data_dir().join("mut_paint").join("brushes")];
+    if let Ok(cwd) = std::env::current_dir() {
+        dirs.push(cwd.join("brushes"));
+    }
+    dirs
+}
+
+// Carrega todos os pinceis de uma pasta; arquivos invalidos sao ignorados.
+pub fn load_dir(dir: &Path) -> Vec<BrushDefinition> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    paths.sort();
+
+    let mut brushes = Vec::new();
+    for path in paths {
+        match load_file(&path) {
+            Ok(loaded) => brushes.extend(loaded),
+            Err(e) => eprintln!("Erro ao carregar pincel {}: {}", path.display(), e),
+        }
+    }
+    brushes
+}
+
+fn load_file(path: &Path) -> Result<Vec<BrushDefinition>> {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let mut brushes = match extension.as_str() {
+        "png" => {
+            let name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            vec![BrushDefinition::from_bytes(&name, &fs::read(path)?)?]
+        }
+        "gbr" | "gih" | "abr" | "myb" => import::load_file(path)?,
+        _ if preset::is_preset(path) => return preset::load_file(path),
+        _ => return Ok(Vec::new()),
+    };
+
+    // Arquivo lateral "<nome>.ini" com os ajustes do pincel.
+    let sidecar = path.with_extension("ini");
+    if sidecar.is_file() {
+        let (name, settings) = read_sidecar(&sidecar)?;
+        for brush in brushes.iter_mut() {
+            brush.set_settings(settings.clone());
+        }
+        if let (Some(name), [brush]) = (name, brushes.as_mut_slice()) {
+            brush.set_name(&name);
+        }
+    }
+    Ok(brushes)
+}
+
+fn read_sidecar(path: &Path) -> Result<(Option<String>, BrushSettings)> {
+    let key_file = KeyFile::new();
+    key_file.load_from_file(path, KeyFileFlags::NONE)?;
+
+    let number = |key: &str| key_file.double(SETTINGS_GROUP, key).ok();
+    let settings = BrushSettings {
+        size: number("size"),
+        spacing: number("spacing"),
+        opacity: number("opacity"),
+        flow: number("flow"),
+        hardness: number("hardness"),
+        airbrush_rate: number("airbrush-rate"),
+        ..BrushSettings::default()
+    };
+    let name = key_file
+        .string(SETTINGS_GROUP, "name")
+        .ok()
+        .map(|s| s.to_string());
+    Ok((name, settings))
+}
+
+// Espera entre o ultimo evento e o recarregamento; uma copia de arquivo
+// gera varios eventos seguidos.
+const RELOAD_DELAY: Duration = Duration::from_millis(200);
+
+type Listeners = Rc<RefCell<Vec<Rc<dyn Fn()>>>>;
+
+// Observa as pastas e marca a biblioteca para recarregar quando um arquivo
+// termina de ser escrito, aparece ou some.
+pub struct LibraryWatcher {
+    _monitors: Vec<FileMonitor>,
+    dirty: Rc<Cell<bool>>,
+    listeners: Listeners,
+}
+
+impl LibraryWatcher {
+    pub fn new(dirs: &[PathBuf]) -> Self {
+        let dirty = Rc::new(Cell::new(false));
+        let listeners: Listeners = Rc::default();
+        let pending: Rc<Cell<Option<SourceId>>> = Rc::default();
+        let mut monitors = Vec::new();
+        for dir in dirs {
+            let file = gio::File::for_path(dir);
+            match file.monitor_directory(FileMonitorFlags::WATCH_MOVES, gio::Cancellable::NONE) {
+                Ok(monitor) => {
+                    let dirty = dirty.clone();
+                    let listeners = listeners.clone();
+                    let pending = pending.clone();
+                    monitor.connect_changed(move |_, _, _, event| {
+                        if !is_relevant(event) {
+                            return;
+                        }
+                        dirty.set(true);
+                        if let Some(source) = pending.take() {
+                            source.remove();
+                        }
+                        let listeners = listeners.clone();
+                        let fired = pending.clone();
+                        let source = glib::timeout_add_local_once(RELOAD_DELAY, move || {
+                            fired.set(None);
+                            let listeners: Vec<_> = listeners.borrow().clone();
+                            for f in listeners {
+                                f();
+                            }
+                        });
+                        pending.set(Some(source));
+                    });
+                    monitors.push(monitor);
+                }
+                Err(e) => eprintln!("Erro ao observar {}: {}", dir.display(), e),
+            }
+        }
+
+        LibraryWatcher {
+            _monitors: monitors,
+            dirty,
+            listeners,
+        }
+    }
+
+    // Avisa `f` uma vez por rajada de mudancas, depois de marcar a biblioteca.
+    pub fn connect_changed(&self, f: impl Fn() + 'static) {
+        self.listeners.borrow_mut().push(Rc::new(f));
+    }
+
+    pub fn take_changed(&self) -> bool {
+        self.dirty.replace(false)
+    }
+}
+
+fn is_relevant(event: FileMonitorEvent) -> bool {
+    matches!(
+        event,
+        FileMonitorEvent::ChangesDoneHint
+            | FileMonitorEvent::Created
+            | FileMonitorEvent::Deleted
+            | FileMonitorEvent::MovedIn
+            | FileMonitorEvent::MovedOut
+            | FileMonitorEvent::Renamed
+    )
+}
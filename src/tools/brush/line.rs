@@ -0,0 +1,79 @@
+use crate::geometry::Point;
+use anyhow::{Result, bail};
+use gtk::cairo::{Context, Format, ImageSurface, Operator};
+use rand::rngs::StdRng;
+
+// Passo do angulo de uma linha com Ctrl pressionado.
+const SNAP_DEGREES: f64 = 15.0;
+
+// Linha reta de um traco: com Ctrl, o traco e refeito da ancora ate o
+// ponteiro a cada movimento, como um elastico.
+pub struct StraightLine {
+    pub anchor: Point,
+    pub pressure: f64,
+    // A ancora ainda nao tem carimbo (inicio do traco).
+    pub stamp: bool,
+    // Mesma sequencia aleatoria a cada redesenho, para a linha nao tremer.
+    rng: StdRng,
+    pub active: bool,
+    // Traco como estava quando a linha comecou; cada redesenho parte dele.
+    snapshot: Option<ImageSurface>,
+}
+
+impl StraightLine {
+    pub fn new(anchor: Point, pressure: f64, stamp: bool, rng: &StdRng) -> Self {
+        StraightLine {
+            anchor,
+            pressure,
+            stamp,
+            rng: rng.clone(),
+            active: false,
+            snapshot: None,
+        }
+    }
+
+    // Ativa a linha guardando o que ja foi pintado no traco.
+    pub fn begin(&mut self, ctx: &Context) -> Result<()> {
+        self.active = true;
+        let Ok(target) = ImageSurface::try_from(ctx.target()) else {
+            bail!("Traco sem superficie de imagem");
+        };
+        let snapshot = ImageSurface::create(Format::ARgb32, target.width(), target.height())?;
+        let copy = Context::new(&snapshot)?;
+        copy.set_source_surface(&target, 0.0, 0.0)?;
+        copy.paint()?;
+        drop(copy);
+        self.snapshot = Some(snapshot);
+        Ok(())
+    }
+
+    // Volta o traco ao estado do inicio da linha.
+    pub fn restore(&self, ctx: &Context) -> Result<()> {
+        ctx.save()?;
+        match self.snapshot.as_ref() {
+            Some(snapshot) => {
+                ctx.set_operator(Operator::Source);
+                ctx.set_source_surface(snapshot, 0.0, 0.0)?;
+            }
+            None => ctx.set_operator(Operator::Clear),
+        }
+        ctx.paint()?;
+        ctx.restore()?;
+        Ok(())
+    }
+
+    pub fn rng(&self) -> StdRng {
+        self.rng.clone()
+    }
+
+    // Projeta `point` na direcao multipla de 15 graus mais proxima.
+    pub fn snap(&self, point: Point) -> Point {
+        let dx = point.x - self.anchor.x;
+        let dy = point.y - self.anchor.y;
+        let step = SNAP_DEGREES.to_radians();
+        let angle = (dy.atan2(dx) / step).round() * step;
+        let (sin, cos) = angle.sin_cos();
+        let length = (dx * cos + dy * sin).max(0.0);
+        Point::new(self.anchor.x + cos * length, self.anchor.y + sin * length)
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{
+    core::{color::Color, pixel_buffer::PixelBuffer},
+    filters::FilterParam,
+    geometry::Point,
+};
+use gtk::cairo::ImageSurface;
+
+// Lado maximo da grade de amostragem sob o carimbo.
+const SAMPLE_GRID: i32 = 8;
+// Perda de tinta por carimbo com carga zero.
+const DEPLETION: f64 = 0.02;
+
+// Pincel misturador: a cor do carimbo mistura a tinta do pincel com a da imagem.
+pub struct MixerBrush {
+    pub enabled: bool,
+    // Quanta tinta o pincel carrega (1 = nunca acaba).
+    pub load: f64,
+    // Proporcao da cor da imagem na cor do carimbo.
+    pub mix: f64,
+    // Quanto da cor da imagem contamina a tinta do pincel.
+    pub wetness: f64,
+    reservoir: Color,
+    paint_left: f64,
+    source: Option<ImageSurface>,
+}
+
+impl MixerBrush {
+    pub fn new() -> Self {
+        MixerBrush {
+            enabled: false,
+            load: 0.5,
+            mix: 0.5,
+            wetness: 0.5,
+            reservoir: Color::BLACK,
+            paint_left: 1.0,
+            source: None,
+        }
+    }
+
+    pub fn params(&self) -> Vec<FilterParam> {
+        vec![
+            FilterParam::toggle("mixer", "Enabled", self.enabled).in_group("Mixer"),
+            FilterParam::new("mixer-load", "Load", 0.0, 1.0, 0.01, self.load).in_group("Mixer"),
+            FilterParam::new("mixer-mix", "Mix", 0.0, 1.0, 0.01, self.mix).in_group("Mixer"),
+            FilterParam::new("mixer-wetness", "Wetness", 0.0, 1.0, 0.01, self.wetness)
+                .in_group("Mixer"),
+        ]
+    }
+
+    // Retorna false para chaves que nao sao do misturador.
+    pub fn set_param(&mut self, key: &str, value: f64) -> bool {
+        match key {
+            "mixer" => self.enabled = value >= 0.5,
+            "mixer-load" => self.load = value.clamp(0.0, 1.0),
+            "mixer-mix" => self.mix = value.clamp(0.0, 1.0),
+            "mixer-wetness" => self.wetness = value.clamp(0.0, 1.0),
+            _ => return false,
+        }
+        true
+    }
+
+    // Recarrega o pincel; a imagem e amostrada como estava no inicio do traco.
+    pub fn begin(&mut self, foreground: Color, image: Option<&ImageSurface>) {
+        self.reservoir = foreground;
+        self.paint_left = 1.0;
+        self.source = image.cloned();
+    }
+
+    pub fn end(&mut self) {
+        self.source = None;
+    }
+
+    // Cor e fator de alfa de um carimbo centrado em `center`.
+    pub fn stamp(&mut self, center: Point, thickness: f64) -> (Color, f64) {
+        let alpha = self.paint_left;
+        self.paint_left *= 1.0 - (1.0 - self.load) * DEPLETION;
+
+        let Some(canvas) = self.sample(center, thickness) else {
+            return (self.reservoir, alpha);
+        };
+        let color = self.reservoir.mix(&canvas, self.mix);
+        self.reservoir = self.reservoir.mix(&canvas, self.wetness);
+        (color, alpha)
+    }
+
+    fn sample(&self, center: Point, thickness: f64) -> Option<Color> {
+        sample_color(self.source.as_ref()?, center, thickness)
+    }
+}
+
+// Media ponderada pelo alfa sob o carimbo; None em area transparente.
+pub fn sample_color(image: &ImageSurface, center: Point, thickness: f64) -> Option<Color> {
+    let radius = (thickness / 2.0).max(1.0);
+    let x = (center.x - radius).floor() as i32;
+    let y = (center.y - radius).floor() as i32;
+    let size = (radius * 2.0).ceil() as i32;
+    let buffer = PixelBuffer::from_surface(image, x, y, size, size).ok()?;
+
+    let step = (size / SAMPLE_GRID).max(1);
+    let mut sum = [0.0f32; 3];
+    let mut total = 0.0f32;
+    for py in (y..y + size).step_by(step as usize) {
+        for px in (x..x + size).step_by(step as usize) {
+            let [r, g, b, a] = buffer.get_premultiplied(px, py);
+            sum[0] += r;
+            sum[1] += g;
+            sum[2] += b;
+            total += a;
+        }
+    }
+    if total <= 0.0 {
+        return None;
+    }
+    Some(Color::new(
+        (sum[0] / total) as f64,
+        (sum[1] / total) as f64,
+        (sum[2] / total) as f64,
+    ))
+}
@@ -1,109 +1,721 @@
+mod blend_mode;
 pub(crate) mod brush_definition;
 mod brush_manager;
+mod dual;
+mod dynamics;
+mod import;
+mod library;
+mod line;
+mod mixer;
+mod mypaint;
+mod paper;
+mod preset;
+mod smoothing;
+mod tip;
+mod variant_cache;
 
 use crate::{
-    core::{canvas::Canvas, event::AppEvents},
-    geometry::Point,
+    core::{
+        assistant::AssistedStroke,
+        canvas::{self, Canvas, StrokeBlend},
+        color::Color,
+        event::{AppEvents, DeviceTool, PointerEvent},
+        pixel_buffer::PixelBuffer,
+    },
+    filters::FilterParam,
+    geometry::{Curve, Point, Rect},
     program::ProgramState,
 };
+use anyhow::Result;
+use blend_mode::BlendMode;
+use brush_definition::{BrushDefinition, BrushSettings};
 use brush_manager::BrushManager;
-use gtk::cairo::Context;
-use rand::Rng;
+use dual::DualBrush;
+use dynamics::BrushDynamics;
+use gtk::cairo::{Context, Format, ImageSurface, Matrix, Operator};
+use line::StraightLine;
+use mixer::MixerBrush;
+use mypaint::MypaintEngine;
+use paper::PaperTexture;
+use rand::rngs::StdRng;
+use smoothing::StrokeSmoother;
+use std::path::Path;
+use variant_cache::CacheStats;
 
 pub struct BrushTool {
     brush_manager: BrushManager,
     is_drawing: bool,
+    erasing: bool,
     last_point: Point,
+    last_pressure: f64,
+    distance_to_next: f64,
+    smoother: StrokeSmoother,
     thickness: f64,
     spacing: f64,
+    opacity: f64,
+    flow: f64,
+    hardness: f64,
+    blend_mode: BlendMode,
+    // Carimbos por segundo com o botao parado; 0 desliga o aerografo.
+    airbrush_rate: f64,
+    last_direction: f64,
+    // Direcao da inclinacao da caneta, em graus.
+    tilt_deg: f64,
+    // Area pintada no traco desde o ultimo aviso ao canvas.
+    damage: Option<Rect>,
+    // Fim do ultimo traco, origem da linha com Shift-clique.
+    stroke_end: Option<Point>,
+    line: Option<StraightLine>,
+    // Guia do assistente que prende os pontos do traco atual.
+    assist: Option<AssistedStroke>,
+    // Copias da simetria do documento, lidas no inicio do traco.
+    symmetry: Vec<Matrix>,
+    paper: PaperTexture,
+    dual: DualBrush,
+    mixer: MixerBrush,
+    // Presente durante tracos com um pincel .myb.
+    mypaint: Option<MypaintEngine>,
+    dynamics: BrushDynamics,
+    rng: StdRng,
+    foreground: Color,
+    background: Color,
+    pressure_size: Curve,
+    pressure_opacity: Curve,
 }
 
 impl BrushTool {
     pub fn new() -> Self {
         let mut brush_manager = BrushManager::new();
         brush_manager.load_default().unwrap();
+        brush_manager.load_library();
 
         BrushTool {
             brush_manager: brush_manager,
             is_drawing: false,
+            erasing: false,
             last_point: Point::ZERO,
+            last_pressure: 1.0,
+            distance_to_next: 0.0,
+            smoother: StrokeSmoother::new(),
             thickness: 15.0,
             spacing: 0.1,
+            opacity: 1.0,
+            flow: 1.0,
+            hardness: 1.0,
+            blend_mode: BlendMode::Normal,
+            airbrush_rate: 0.0,
+            last_direction: 0.0,
+            tilt_deg: 0.0,
+            damage: None,
+            stroke_end: None,
+            line: None,
+            assist: None,
+            symmetry: vec![Matrix::identity()],
+            paper: PaperTexture::new(),
+            dual: DualBrush::new(),
+            mixer: MixerBrush::new(),
+            mypaint: None,
+            dynamics: BrushDynamics::new(),
+            rng: BrushDynamics::new().stroke_rng(),
+            foreground: Color::BLACK,
+            background: Color::WHITE,
+            pressure_size: Curve::new(vec![(0.0, 0.1), (1.0, 1.0)]),
+            pressure_opacity: Curve::new(vec![(0.0, 0.3), (1.0, 1.0)]),
+        }
+    }
+
+    // Curva 0 leva a pressao ao tamanho, curva 1 a opacidade.
+    pub fn pressure_curve(&self, index: usize) -> Option<&Curve> {
+        match index {
+            0 => Some(&self.pressure_size),
+            1 => Some(&self.pressure_opacity),
+            _ => None,
+        }
+    }
+
+    pub fn set_pressure_curve(&mut self, index: usize, curve: Curve) {
+        match index {
+            0 => self.pressure_size = curve,
+            1 => self.pressure_opacity = curve,
+            _ => {}
+        }
+    }
+
+    pub fn set_colors(&mut self, foreground: Color, background: Color) {
+        self.foreground = foreground;
+        self.background = background;
+    }
+
+    pub fn dynamics_params(&self) -> Vec<FilterParam> {
+        self.dynamics.params()
+    }
+
+    pub fn set_dynamics_param(&mut self, key: &str, value: f64) {
+        self.dynamics.set_param(key, value);
+    }
+
+    pub fn params(&self) -> Vec<FilterParam> {
+        vec![
+            FilterParam::new("size", "Size", 1.0, 500.0, 1.0, self.thickness).in_group("Brush"),
+            FilterParam::new("spacing", "Spacing", 0.1, 100.0, 0.1, self.spacing).in_group("Brush"),
+            FilterParam::new("opacity", "Opacity", 0.0, 1.0, 0.01, self.opacity).in_group("Brush"),
+            FilterParam::new("flow", "Flow", 0.0, 1.0, 0.01, self.flow).in_group("Brush"),
+            FilterParam::new("hardness", "Hardness", 0.0, 1.0, 0.01, self.hardness)
+                .in_group("Brush"),
+            FilterParam::new(
+                "airbrush-rate",
+                "Airbrush rate (0 off)",
+                0.0,
+                60.0,
+                1.0,
+                self.airbrush_rate,
+            )
+            .in_group("Brush"),
+            FilterParam::new(
+                "lazy-distance",
+                "Lazy distance",
+                0.0,
+                100.0,
+                1.0,
+                self.smoother.lazy_distance,
+            )
+            .in_group("Smoothing"),
+            FilterParam::new(
+                "average-window",
+                "Average window",
+                1.0,
+                16.0,
+                1.0,
+                self.smoother.average_window as f64,
+            )
+            .in_group("Smoothing"),
+            FilterParam::toggle("spline", "Spline", self.smoother.spline).in_group("Smoothing"),
+        ]
+        .into_iter()
+        .chain(self.paper.params())
+        .chain(self.dual.params())
+        .chain(self.mixer.params())
+        .collect()
+    }
+
+    pub fn set_param(&mut self, key: &str, value: f64) {
+        match key {
+            "size" => self.thickness = value.max(1.0),
+            "spacing" => self.spacing = value.max(0.0),
+            "opacity" => self.opacity = value.clamp(0.0, 1.0),
+            "flow" => self.flow = value.clamp(0.0, 1.0),
+            "hardness" => self.hardness = value.clamp(0.0, 1.0),
+            "airbrush-rate" => self.airbrush_rate = value.clamp(0.0, 60.0),
+            "lazy-distance" => self.smoother.lazy_distance = value.max(0.0),
+            "average-window" => self.smoother.average_window = value.round().max(1.0) as usize,
+            "spline" => self.smoother.spline = value >= 0.5,
+            _ => {
+                if !self.paper.set_param(key, value) && !self.dual.set_param(key, value) {
+                    self.mixer.set_param(key, value);
+                }
+            }
+        }
+    }
+
+    pub fn blend_modes() -> Vec<&'static str> {
+        BlendMode::ALL.iter().map(|mode| mode.label()).collect()
+    }
+
+    pub fn blend_mode(&self) -> usize {
+        self.blend_mode.index()
+    }
+
+    pub fn set_blend_mode(&mut self, index: usize) {
+        if let Some(mode) = BlendMode::ALL.get(index) {
+            self.blend_mode = *mode;
+        }
+    }
+
+    pub fn dual_brush(&self) -> Option<String> {
+        self.dual.brush_name.clone()
+    }
+
+    pub fn set_dual_brush(&mut self, name: Option<String>) {
+        self.dual.brush_name = name;
+    }
+
+    pub fn load_paper_texture(&mut self, path: &Path) -> Result<()> {
+        self.paper.load(path)
+    }
+
+    // Nomes ordenados e o nome do pincel ativo.
+    pub fn brush_names(&self) -> (Vec<String>, String) {
+        let mut names: Vec<String> = self.brush_manager.brushes.keys().cloned().collect();
+        names.sort();
+        (names, self.brush_manager.active_brush_name.clone())
+    }
+
+    pub fn brush_thumbnail(&mut self, name: &str) -> Option<ImageSurface> {
+        self.brush_manager.thumbnail(name)
+    }
+
+    pub fn cache_stats(&self) -> CacheStats {
+        let mut total = CacheStats::default();
+        for brush in self.brush_manager.brushes.values() {
+            total.add(&brush.cache_stats());
+        }
+        total
+    }
+
+    pub fn select_brush(&mut self, name: &str) {
+        if self.brush_manager.brushes.contains_key(name) {
+            self.brush_manager.active_brush_name = name.into();
+            self.apply_brush_settings();
+        }
+    }
+
+    pub fn connect_library_changed(&self, f: impl Fn() + 'static) {
+        self.brush_manager.connect_library_changed(f);
+    }
+
+    // Verdadeiro quando a pasta de pinceis mudou desde a ultima chamada.
+    pub fn reload_brushes(&mut self) -> bool {
+        self.brush_manager.reload_if_changed()
+    }
+
+    pub fn import_brushes(&mut self, path: &Path) -> Result<usize> {
+        let count = self.brush_manager.import_file(path)?;
+        self.apply_brush_settings();
+        Ok(count)
+    }
+
+    // Cria um pincel com o conteudo de `pixels` e passa a usa-lo.
+    pub fn define_brush(&mut self, name: &str, pixels: &PixelBuffer) -> Result<String> {
+        let brush = BrushDefinition::from_pixels(name, pixels)?;
+        let name = self.brush_manager.add_brush(brush);
+        self.apply_brush_settings();
+        Ok(name)
+    }
+
+    fn apply_brush_settings(&mut self) {
+        let Some(brush) = self.brush_manager.get_active_brush_mut() else {
+            return;
+        };
+        let settings = brush.settings().clone();
+        if let Some(size) = settings.size {
+            self.thickness = size.max(1.0);
+        }
+        if let Some(spacing) = settings.spacing {
+            self.spacing = spacing.max(0.0);
+        }
+        if let Some(opacity) = settings.opacity {
+            self.opacity = opacity.clamp(0.0, 1.0);
+        }
+        if let Some(flow) = settings.flow {
+            self.flow = flow.clamp(0.0, 1.0);
+        }
+        if let Some(hardness) = settings.hardness {
+            self.hardness = hardness.clamp(0.0, 1.0);
+        }
+        if let Some(blend_mode) = settings.blend_mode {
+            self.blend_mode = blend_mode;
+        }
+        if let Some(rate) = settings.airbrush_rate {
+            self.airbrush_rate = rate.clamp(0.0, 60.0);
+        }
+        if let Some(dynamics) = settings.dynamics {
+            self.dynamics = dynamics;
+        }
+    }
+
+    fn current_settings(&self) -> BrushSettings {
+        BrushSettings {
+            size: Some(self.thickness),
+            spacing: Some(self.spacing),
+            opacity: Some(self.opacity),
+            flow: Some(self.flow),
+            hardness: Some(self.hardness),
+            blend_mode: Some(self.blend_mode),
+            airbrush_rate: Some(self.airbrush_rate),
+            dynamics: Some(self.dynamics.clone()),
+        }
+    }
+
+    // Salva o pincel ativo com os ajustes atuais da ferramenta.
+    pub fn save_preset(&mut self, path: &Path) -> Result<()> {
+        self.store_settings();
+        self.brush_manager.save_active(path)
+    }
+
+    pub fn export_presets(&mut self, path: &Path) -> Result<()> {
+        self.store_settings();
+        self.brush_manager.save_bundle(path)
+    }
+
+    fn store_settings(&mut self) {
+        let settings = self.current_settings();
+        if let Some(brush) = self.brush_manager.get_active_brush_mut() {
+            brush.set_settings(settings);
         }
     }
 
     pub fn on_event(&mut self, events: AppEvents, canvas: &mut Canvas, state: &mut ProgramState) {
         match events {
-            AppEvents::MouseDown(point) => {
-                if let Some(ctx) = canvas.get_image_context() {
-                    let image_point = canvas.screen_to_canvas_coords(point);
-                    self.last_point = image_point;
+            AppEvents::MouseDown(event) => {
+                self.erasing = event.device == DeviceTool::Eraser;
+                self.tilt_deg = Self::tilt_direction(&event);
+                if let Err(e) = canvas.begin_stroke(self.opacity, self.stroke_blend()) {
+                    eprintln!("Erro ao iniciar o traco: {}", e);
+                    return;
+                }
+                self.damage = None;
+                if let Some(ctx) = canvas.stroke_context() {
+                    let mut image_point = canvas.screen_to_canvas_coords(event.point);
+                    self.assist = canvas.assisted_stroke(image_point);
+                    if let Some(assist) = self.assist.as_mut() {
+                        image_point = assist.project(image_point);
+                    }
+                    // Com Shift o fim do traco anterior ja tem carimbo.
+                    let (origin, stamp) = match self.stroke_end {
+                        Some(end) if event.modifiers.shift => (end, false),
+                        _ => (image_point, true),
+                    };
                     self.is_drawing = true;
-                    self.draw_stamp(&ctx, image_point);
+                    self.rng = self.dynamics.stroke_rng();
+                    self.symmetry = canvas.symmetry_transforms();
+                    self.mypaint = self
+                        .brush_manager
+                        .get_active_brush_mut()
+                        .and_then(|brush| brush.mypaint_settings().cloned())
+                        .map(MypaintEngine::new);
+                    if let Some(engine) = self.mypaint.as_mut() {
+                        engine.set_copies(self.symmetry.clone());
+                    }
+                    self.line = Some(StraightLine::new(origin, event.pressure, stamp, &self.rng));
+                    self.smoother.begin((image_point, event.pressure));
+                    if event.modifiers.ctrl {
+                        self.draw_line(&ctx, canvas.image(), image_point, event.pressure);
+                    } else {
+                        self.start_at(&ctx, canvas.image(), origin, event.pressure, stamp);
+                        if !stamp {
+                            self.draw_stamps(&ctx, image_point, event.pressure);
+                        }
+                    }
+                    if self.airbrush_rate > 0.0 {
+                        state.request_ticks((1000.0 / self.airbrush_rate).round() as u64);
+                    }
+                    self.flush_damage(canvas);
                     state.request_paint();
                 }
             }
-            AppEvents::MouseMove(point) => {
+            AppEvents::MouseMove(event) => {
                 if !self.is_drawing {
                     return;
                 }
+                self.tilt_deg = Self::tilt_direction(&event);
 
-                if let Some(ctx) = canvas.get_image_context() {
-                    let image_point = canvas.screen_to_canvas_coords(point);
-                    self.draw_stamps(&ctx, self.last_point, image_point);
-                    self.last_point = image_point;
+                if let Some(ctx) = canvas.stroke_context() {
+                    let mut image_point = canvas.screen_to_canvas_coords(event.point);
+                    if let Some(assist) = self.assist.as_mut() {
+                        image_point = assist.project(image_point);
+                    }
+                    if event.modifiers.ctrl {
+                        // Ctrl no meio do traco: a linha parte de onde o traco esta.
+                        if self.line.as_ref().is_some_and(|line| !line.active) {
+                            let line = StraightLine::new(
+                                self.last_point,
+                                self.last_pressure,
+                                false,
+                                &self.rng,
+                            );
+                            self.line = Some(line);
+                        }
+                        self.draw_line(&ctx, canvas.image(), image_point, event.pressure);
+                        self.flush_damage(canvas);
+                        state.request_paint();
+                        return;
+                    }
+                    // Ctrl solto: o traco livre continua do fim da linha.
+                    if let Some(line) = self.line.as_mut()
+                        && line.active
+                    {
+                        line.active = false;
+                        self.smoother.begin((self.last_point, self.last_pressure));
+                    }
+                    for (point, pressure) in self.smoother.push((image_point, event.pressure)) {
+                        self.draw_stamps(&ctx, point, pressure);
+                    }
+                    self.flush_damage(canvas);
                     state.request_paint();
                 }
             }
             AppEvents::MouseUp(_) => {
+                if !self.is_drawing {
+                    return;
+                }
+                let straight = self.line.take().is_some_and(|line| line.active);
+                if !straight && let Some(ctx) = canvas.stroke_context() {
+                    for (point, pressure) in self.smoother.finish() {
+                        self.draw_stamps(&ctx, point, pressure);
+                    }
+                }
+                self.stroke_end = Some(self.last_point);
+                self.assist = None;
+                self.flush_damage(canvas);
+                if let Err(e) = canvas.commit_stroke() {
+                    eprintln!("Erro ao aplicar o traco: {}", e);
+                }
+                state.stop_ticks();
+                state.request_paint();
+                state.request_cache_sync();
+                self.mixer.end();
+                self.mypaint = None;
                 self.is_drawing = false;
-                state.stop_request_paint();
+            }
+            AppEvents::Tick => {
+                // Aerografo: com o ponteiro parado o traco continua acumulando.
+                if !self.is_drawing || self.airbrush_rate <= 0.0 {
+                    state.stop_ticks();
+                    return;
+                }
+                if let Some(ctx) = canvas.stroke_context() {
+                    if let Some(engine) = self.mypaint.as_mut() {
+                        engine.stroke_to(&ctx, &mut self.rng, self.last_point, self.last_pressure);
+                        self.flush_damage(canvas);
+                        state.request_paint();
+                        return;
+                    }
+                    self.draw_stamp(
+                        &ctx,
+                        self.last_point,
+                        self.last_pressure,
+                        self.last_direction,
+                    );
+                    self.flush_damage(canvas);
+                    state.request_paint();
+                }
             }
             _ => {}
         }
     }
 
-    fn draw_stamp(&mut self, ctx: &Context, image_point: Point) {
-        let mut rng = rand::rng();
-        let angle_step = 15;
-        let n_steps = 360 / angle_step;
-        let angle_index = rng.random_range(0..n_steps);
-        let angle_deg = angle_index * angle_step;
+    // Passa ao canvas a area pintada, para a mistura do preview nao varrer a imagem.
+    fn flush_damage(&mut self, canvas: &mut Canvas) {
+        let engine = self
+            .mypaint
+            .as_mut()
+            .and_then(|engine| engine.take_damage());
+        let damage = match (self.damage.take(), engine) {
+            (Some(a), Some(b)) => Some(a.union(&b)),
+            (a, b) => a.or(b),
+        };
+        if let Some(rect) = damage {
+            canvas.mark_stroke(rect);
+        }
+    }
 
-        if let Some(brush) = self.brush_manager.get_active_brush_mut() {
-            let cached = brush.get_cached(self.thickness, angle_deg);
+    // Comeca o traco em `point`, com ou sem um carimbo inicial.
+    fn start_at(
+        &mut self,
+        ctx: &Context,
+        image: Option<&ImageSurface>,
+        point: Point,
+        pressure: f64,
+        stamp: bool,
+    ) {
+        self.last_point = point;
+        self.last_pressure = pressure;
+        self.distance_to_next = self.stamp_spacing();
+        self.last_direction = 0.0;
+        self.mixer.begin(self.foreground, image);
+        if let Some(engine) = self.mypaint.as_mut() {
+            engine.begin(self.foreground, image, point, pressure);
+            if stamp {
+                engine.first_dab(ctx, &mut self.rng);
+            }
+        } else if stamp {
+            self.draw_stamp(ctx, point, pressure, 0.0);
+        }
+    }
+
+    // Refaz o traco como uma linha reta da ancora ate `end`, em passos de 15 graus.
+    fn draw_line(
+        &mut self,
+        ctx: &Context,
+        image: Option<&ImageSurface>,
+        end: Point,
+        pressure: f64,
+    ) {
+        let Some(line) = self.line.as_mut() else {
+            return;
+        };
+        let result = if line.active {
+            line.restore(ctx)
+        } else {
+            line.begin(ctx)
+        };
+        if let Err(e) = result {
+            eprintln!("Erro ao redesenhar a linha: {}", e);
+            return;
+        }
+        let (anchor, anchor_pressure, stamp) = (line.anchor, line.pressure, line.stamp);
+        let end = line.snap(end);
+        self.rng = line.rng();
+
+        self.start_at(ctx, image, anchor, anchor_pressure, stamp);
+        self.draw_stamps(ctx, end, pressure);
+    }
 
-            ctx.save().unwrap();
-            let x = image_point.x - (cached.width() as f64) / 2.0;
-            let y = image_point.y - (cached.height() as f64) / 2.0;
-            ctx.set_source_surface(cached, x, y).unwrap();
-            ctx.paint().unwrap();
-            ctx.restore().unwrap();
+    // Cada carimbo e gerado uma vez no espaco da imagem e repetido em cada
+    // copia da simetria, assim as copias sao espelhos exatos umas das outras.
+    fn draw_stamp(&mut self, ctx: &Context, image_point: Point, pressure: f64, direction_deg: f64) {
+        for _ in 0..self.dynamics.stamp_count {
+            let Some((dab, x, y)) = self.render_dab(image_point, pressure, direction_deg) else {
+                return;
+            };
+            let (width, height) = (dab.width() as f64, dab.height() as f64);
+            for copy in &self.symmetry {
+                ctx.save().unwrap();
+                ctx.transform(*copy);
+                ctx.set_source_surface(&dab, x, y).unwrap();
+                ctx.paint().unwrap();
+                let rect = canvas::device_rect(ctx, x, y, width, height);
+                self.damage = Some(self.damage.map_or(rect, |damage| damage.union(&rect)));
+                ctx.restore().unwrap();
+            }
         }
     }
 
-    fn draw_stamps(&mut self, ctx: &Context, start: Point, end: Point) {
-        let dx = end.x - start.x;
-        let dy = end.y - start.y;
-        let distance = dx.hypot(dy);
+    // Carimbo pronto (cor, segunda ponta e papel) e sua posicao na imagem.
+    fn render_dab(
+        &mut self,
+        image_point: Point,
+        pressure: f64,
+        direction_deg: f64,
+    ) -> Option<(ImageSurface, f64, f64)> {
+        let base_thickness = self.thickness * self.pressure_size.eval(pressure);
+        // A opacidade limita o traco inteiro; aqui so entra o fluxo por carimbo.
+        let base_alpha = self.pressure_opacity.eval(pressure) * self.flow;
+
+        let variation = self.dynamics.sample(
+            &mut self.rng,
+            direction_deg,
+            self.tilt_deg,
+            self.foreground,
+            self.background,
+        );
+        let thickness = (base_thickness * variation.size).max(1.0);
+        let alpha = base_alpha * variation.opacity * variation.flow;
+        let center = Point::new(
+            image_point.x + variation.offset.x * thickness,
+            image_point.y + variation.offset.y * thickness,
+        );
+
+        let brush = self.brush_manager.get_active_brush_mut()?;
+        brush.set_hardness(self.hardness);
+        let cached = match brush.get_cached(thickness, variation.angle_deg) {
+            Ok(cached) => cached.clone(),
+            Err(e) => {
+                eprintln!("Erro ao gerar o carimbo: {}", e);
+                return None;
+            }
+        };
 
+        let x = center.x - (cached.width() as f64) / 2.0;
+        let y = center.y - (cached.height() as f64) / 2.0;
+        let cached = match self.dual.mask(&cached, x, y, &mut self.brush_manager) {
+            Ok(Some(masked)) => masked,
+            Ok(None) => cached,
+            Err(e) => {
+                eprintln!("Erro ao aplicar a segunda ponta: {}", e);
+                return None;
+            }
+        };
+        let (color, alpha) = if self.mixer.enabled {
+            let (color, paint) = self.mixer.stamp(center, thickness);
+            (color, alpha * paint)
+        } else {
+            (variation.color, alpha)
+        };
+
+        let dab = match self.paint_dab(&cached, color, alpha, x, y) {
+            Ok(dab) => dab,
+            Err(e) => {
+                eprintln!("Erro ao gerar o carimbo: {}", e);
+                return None;
+            }
+        };
+        Some((dab, x, y))
+    }
+
+    // Pinta a mascara com a cor e recorta pelo grao do papel, que fica preso a imagem.
+    fn paint_dab(
+        &self,
+        mask: &ImageSurface,
+        color: Color,
+        alpha: f64,
+        x: f64,
+        y: f64,
+    ) -> Result<ImageSurface> {
+        let dab = ImageSurface::create(Format::ARgb32, mask.width(), mask.height())?;
+        let ctx = Context::new(&dab)?;
+        ctx.set_source_rgba(color.r, color.g, color.b, alpha);
+        ctx.mask_surface(mask, 0.0, 0.0)?;
+        if let Some(paper) = self.paper.pattern() {
+            ctx.translate(-x, -y);
+            ctx.set_operator(Operator::DestIn);
+            ctx.set_source(&paper)?;
+            ctx.paint()?;
+        }
+        drop(ctx);
+        Ok(dab)
+    }
+
+    fn tilt_direction(event: &PointerEvent) -> f64 {
+        event.tilt_y.atan2(event.tilt_x).to_degrees()
+    }
+
+    fn stroke_blend(&self) -> StrokeBlend {
+        if self.erasing {
+            BlendMode::Erase.stroke_blend()
+        } else {
+            self.blend_mode.stroke_blend()
+        }
+    }
+
+    fn stamp_spacing(&self) -> f64 {
         const CONTINUOUS_SPACING_FACTOR: f64 = 0.7;
         let ideal_continuous_spacing = self.thickness * CONTINUOUS_SPACING_FACTOR;
-        let spacing_use = self.spacing.min(ideal_continuous_spacing).max(1.0);
-        let steps = (distance / spacing_use).ceil() as u32;
+        self.spacing.min(ideal_continuous_spacing).max(1.0)
+    }
 
-        if steps == 0 {
-            self.draw_stamp(ctx, end);
+    // Carimba de last_point ate end, carregando a sobra de distancia
+    // entre segmentos para manter o espacamento uniforme.
+    fn draw_stamps(&mut self, ctx: &Context, end: Point, end_pressure: f64) {
+        let start = self.last_point;
+        let start_pressure = self.last_pressure;
+        self.last_point = end;
+        self.last_pressure = end_pressure;
+        if let Some(engine) = self.mypaint.as_mut() {
+            engine.stroke_to(ctx, &mut self.rng, end, end_pressure);
             return;
         }
 
-        for i in 0..=steps {
-            let t = (i as f64) / (steps as f64);
+        let dx = end.x - start.x;
+        let dy = end.y - start.y;
+        let distance = dx.hypot(dy);
+        let spacing_use = self.stamp_spacing();
+        let direction_deg = dy.atan2(dx).to_degrees();
+        if distance > 0.0 {
+            self.last_direction = direction_deg;
+        }
+
+        let mut travelled = self.distance_to_next;
+        while travelled <= distance {
+            let t = travelled / distance;
             let p = Point {
                 x: start.x + dx * t,
                 y: start.y + dy * t,
             };
-            self.draw_stamp(ctx, p);
+            let pressure = start_pressure + (end_pressure - start_pressure) * t;
+            self.draw_stamp(ctx, p, pressure, direction_deg);
+            travelled += spacing_use;
         }
+        self.distance_to_next = travelled - distance;
     }
 }
@@ -0,0 +1,247 @@
+use anyhow::{Result, bail};
+use std::{collections::HashMap, iter::Peekable, str::Chars};
+
+// Limite de aninhamento, para que um arquivo hostil nao estoure a pilha
+const MAX_DEPTH: usize = 64;
+
+// Leitor JSON minimo, suficiente para os arquivos .myb.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(HashMap<String, Json>),
+}
+
+impl Json {
+    pub fn parse(text: &str) -> Result<Json> {
+        let mut parser = Parser {
+            chars: text.chars().peekable(),
+            depth: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.chars.peek().is_some() {
+            bail!("Conteudo apos o fim do JSON");
+        }
+        Ok(value)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Json::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&HashMap<String, Json>> {
+        match self {
+            Json::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => bail!("Esperado '{}', encontrado '{}'", expected, c),
+            None => bail!("Esperado '{}', fim do texto", expected),
+        }
+    }
+
+    fn value(&mut self) -> Result<Json> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('{') | Some('[') => self.nested(),
+            Some('"') => Ok(Json::String(self.string()?)),
+            Some('t') => self.literal("true", Json::Bool(true)),
+            Some('f') => self.literal("false", Json::Bool(false)),
+            Some('n') => self.literal("null", Json::Null),
+            Some(c) if *c == '-' || c.is_ascii_digit() => self.number(),
+            Some(c) => bail!("Caractere inesperado '{}'", c),
+            None => bail!("Fim inesperado do JSON"),
+        }
+    }
+
+    fn nested(&mut self) -> Result<Json> {
+        if self.depth >= MAX_DEPTH {
+            bail!("JSON aninhado demais");
+        }
+        self.depth += 1;
+        let value = if self.chars.peek() == Some(&'{') {
+            self.object()
+        } else {
+            self.array()
+        };
+        self.depth -= 1;
+        value
+    }
+
+    fn literal(&mut self, word: &str, value: Json) -> Result<Json> {
+        for expected in word.chars() {
+            if self.chars.next() != Some(expected) {
+                bail!("Literal invalido, esperado {}", word);
+            }
+        }
+        Ok(value)
+    }
+
+    fn number(&mut self) -> Result<Json> {
+        let mut text = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            text.push(c);
+        }
+        match text.parse() {
+            Ok(n) => Ok(Json::Number(n)),
+            Err(_) => bail!("Numero invalido: {}", text),
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        self.expect('"')?;
+        let mut text = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(text),
+                Some('\\') => match self.chars.next() {
+                    Some('n') => text.push('\n'),
+                    Some('t') => text.push('\t'),
+                    Some('r') => text.push('\r'),
+                    Some('b') => text.push('\u{8}'),
+                    Some('f') => text.push('\u{c}'),
+                    Some('u') => {
+                        let hex: String = (0..4).filter_map(|_| self.chars.next()).collect();
+                        let code = u32::from_str_radix(&hex, 16)?;
+                        text.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                    }
+                    Some(c) => text.push(c),
+                    None => bail!("Texto sem fim"),
+                },
+                Some(c) => text.push(c),
+                None => bail!("Texto sem fim"),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.chars.next_if_eq(&']').is_some() {
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Json::Array(items)),
+                _ => bail!("Esperado ',' ou ']'"),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json> {
+        self.expect('{')?;
+        let mut map = HashMap::new();
+        self.skip_whitespace();
+        if self.chars.next_if_eq(&'}').is_some() {
+            return Ok(Json::Object(map));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(':')?;
+            map.insert(key, self.value()?);
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(Json::Object(map)),
+                _ => bail!("Esperado ',' ou '}}'"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_values() {
+        let json = Json::parse(r#" {"a": [1, -2.5e1, true, null], "b": {"c": "x"}} "#).unwrap();
+        let a = json.get("a").and_then(Json::as_array).unwrap();
+        assert_eq!(a[0].as_f64(), Some(1.0));
+        assert_eq!(a[1].as_f64(), Some(-25.0));
+        assert_eq!(a[2], Json::Bool(true));
+        assert_eq!(a[3], Json::Null);
+        let c = json.get("b").and_then(|b| b.get("c"));
+        assert_eq!(c, Some(&Json::String("x".into())));
+    }
+
+    #[test]
+    fn parses_escapes() {
+        let json = Json::parse(r#""a\"b\\c\né""#).unwrap();
+        assert_eq!(json, Json::String("a\"b\\c\né".into()));
+    }
+
+    #[test]
+    fn rejects_unterminated_strings_and_escapes() {
+        for text in [
+            r#""abc"#,
+            r#""abc\"#,
+            r#""\u12"#,
+            r#""\uzzzz""#,
+            r#"{"a": "b}"#,
+        ] {
+            assert!(Json::parse(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_documents() {
+        for text in ["", "[1, 2", "{\"a\" 1}", "[1] 2", "tru", "-", "{1: 2}"] {
+            assert!(Json::parse(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn limits_nesting_depth() {
+        let nested = |depth: usize| "[".repeat(depth) + &"]".repeat(depth);
+        assert!(Json::parse(&nested(MAX_DEPTH)).is_ok());
+        assert!(Json::parse(&nested(MAX_DEPTH + 1)).is_err());
+        // Sem o limite isto estouraria a pilha.
+        assert!(Json::parse(&"{\"a\":[".repeat(100_000)).is_err());
+    }
+}
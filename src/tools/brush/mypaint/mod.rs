@@ -0,0 +1,16 @@
+mod engine;
+mod json;
+mod settings;
+
+pub use engine::MypaintEngine;
+pub use settings::MypaintSettings;
+
+use super::brush_definition::BrushDefinition;
+use anyhow::Result;
+use std::rc::Rc;
+
+// Pincel do MyPaint (.myb, JSON); desenhado pelo MypaintEngine.
+pub fn load(name: &str, data: &[u8]) -> Result<Vec<BrushDefinition>> {
+    let settings = MypaintSettings::parse(&String::from_utf8_lossy(data))?;
+    Ok(vec![BrushDefinition::mypaint(name, Rc::new(settings))])
+}
@@ -0,0 +1,182 @@
+use super::json::Json;
+use anyhow::{Result, bail};
+use std::collections::HashMap;
+
+// Entradas do libmypaint que este motor calcula.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Inputs {
+    pub pressure: f64,
+    pub speed1: f64,
+    pub speed2: f64,
+    pub random: f64,
+}
+
+impl Inputs {
+    fn get(&self, name: &str) -> f64 {
+        match name {
+            "pressure" => self.pressure,
+            "speed1" => self.speed1,
+            "speed2" => self.speed2,
+            "random" => self.random,
+            _ => 0.0,
+        }
+    }
+}
+
+struct Setting {
+    base_value: f64,
+    // Mapeamentos lineares por partes de cada entrada.
+    inputs: Vec<(String, Vec<(f64, f64)>)>,
+}
+
+// Ajustes de um pincel .myb (formato JSON, versao 3).
+pub struct MypaintSettings {
+    settings: HashMap<String, Setting>,
+    source: String,
+}
+
+impl MypaintSettings {
+    pub fn parse(text: &str) -> Result<Self> {
+        let json = Json::parse(text)?;
+        if let Some(version) = json.get("version").and_then(Json::as_f64)
+            && version != 3.0
+        {
+            bail!("Versao de .myb nao suportada: {}", version);
+        }
+        let Some(entries) = json.get("settings").and_then(Json::as_object) else {
+            bail!("Arquivo .myb sem \"settings\"");
+        };
+
+        let mut settings = HashMap::new();
+        for (name, entry) in entries {
+            let base_value = entry
+                .get("base_value")
+                .and_then(Json::as_f64)
+                .unwrap_or(0.0);
+            let mut inputs = Vec::new();
+            for (input, points) in entry
+                .get("inputs")
+                .and_then(Json::as_object)
+                .into_iter()
+                .flatten()
+            {
+                let points: Vec<(f64, f64)> = points
+                    .as_array()
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|p| {
+                        let p = p.as_array()?;
+                        Some((p.first()?.as_f64()?, p.get(1)?.as_f64()?))
+                    })
+                    .collect();
+                if points.len() >= 2 {
+                    inputs.push((input.clone(), points));
+                }
+            }
+            settings.insert(name.clone(), Setting { base_value, inputs });
+        }
+
+        Ok(MypaintSettings {
+            settings,
+            source: text.to_string(),
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    // Valor base somado aos mapeamentos das entradas; `default` se ausente.
+    pub fn value(&self, name: &str, default: f64, inputs: &Inputs) -> f64 {
+        let Some(setting) = self.settings.get(name) else {
+            return default;
+        };
+        setting.base_value
+            + setting
+                .inputs
+                .iter()
+                .map(|(input, points)| interpolate(points, inputs.get(input)))
+                .sum::<f64>()
+    }
+
+    pub fn base_value(&self, name: &str, default: f64) -> f64 {
+        self.settings.get(name).map_or(default, |s| s.base_value)
+    }
+}
+
+fn interpolate(points: &[(f64, f64)], x: f64) -> f64 {
+    let first = points[0];
+    let last = points[points.len() - 1];
+    if x <= first.0 {
+        return first.1;
+    }
+    if x >= last.0 {
+        return last.1;
+    }
+    for pair in points.windows(2) {
+        let (x0, y0) = pair[0];
+        let (x1, y1) = pair[1];
+        if x <= x1 {
+            let t = if x1 > x0 { (x - x0) / (x1 - x0) } else { 0.0 };
+            return y0 + (y1 - y0) * t;
+        }
+    }
+    last.1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BRUSH: &str = r#"{
+        "version": 3,
+        "comment": "teste",
+        "settings": {
+            "opaque": {"base_value": 0.5, "inputs": {}},
+            "radius_logarithmic": {
+                "base_value": 1.0,
+                "inputs": {
+                    "pressure": [[0.0, 0.0], [0.5, 1.0], [1.0, 3.0]],
+                    "random": [[0.0, -1.0]]
+                }
+            }
+        }
+    }"#;
+
+    fn pressure(pressure: f64) -> Inputs {
+        Inputs {
+            pressure,
+            ..Inputs::default()
+        }
+    }
+
+    #[test]
+    fn reads_base_values_and_defaults() {
+        let settings = MypaintSettings::parse(BRUSH).unwrap();
+        assert_eq!(settings.base_value("opaque", 1.0), 0.5);
+        assert_eq!(settings.value("opaque", 1.0, &pressure(1.0)), 0.5);
+        assert_eq!(settings.value("hardness", 0.8, &pressure(1.0)), 0.8);
+        assert_eq!(settings.source(), BRUSH);
+    }
+
+    #[test]
+    fn interpolates_input_curves() {
+        let settings = MypaintSettings::parse(BRUSH).unwrap();
+        let radius = |p: f64| settings.value("radius_logarithmic", 0.0, &pressure(p));
+        // Entrada com um ponto so e ignorada.
+        assert_eq!(radius(0.0), 1.0);
+        assert_eq!(radius(0.25), 1.5);
+        assert_eq!(radius(0.5), 2.0);
+        assert_eq!(radius(0.75), 3.0);
+        // Fora da curva vale o ponto da ponta.
+        assert_eq!(radius(2.0), 4.0);
+        assert_eq!(radius(-1.0), 1.0);
+    }
+
+    #[test]
+    fn rejects_other_versions_and_missing_settings() {
+        assert!(MypaintSettings::parse(r#"{"version": 2, "settings": {}}"#).is_err());
+        assert!(MypaintSettings::parse(r#"{"version": 3}"#).is_err());
+        assert!(MypaintSettings::parse("not json").is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use crate::filters::FilterParam;
+use anyhow::Result;
+use gtk::cairo::{Extend, Format, ImageSurface, Matrix, SurfacePattern};
+use image::DynamicImage;
+use std::path::Path;
+
+// Textura de papel: modula o alfa dos carimbos no espaco da imagem,
+// entao o grao fica fixo no papel ao longo do traco.
+pub struct PaperTexture {
+    height_map: Option<image::GrayImage>,
+    mask: Option<ImageSurface>,
+    pub scale: f64,
+    pub depth: f64,
+    pub invert: bool,
+}
+
+impl PaperTexture {
+    pub fn new() -> Self {
+        PaperTexture {
+            height_map: None,
+            mask: None,
+            scale: 1.0,
+            depth: 0.5,
+            invert: false,
+        }
+    }
+
+    pub fn load(&mut self, path: &Path) -> Result<()> {
+        self.set_image(image::open(path)?)
+    }
+
+    pub fn set_image(&mut self, image: DynamicImage) -> Result<()> {
+        self.height_map = Some(image.to_luma8());
+        self.rebuild_mask()
+    }
+
+    pub fn params(&self) -> Vec<FilterParam> {
+        vec![
+            FilterParam::new("paper-scale", "Scale", 0.1, 4.0, 0.05, self.scale).in_group("Paper"),
+            FilterParam::new("paper-depth", "Depth", 0.0, 1.0, 0.01, self.depth).in_group("Paper"),
+            FilterParam::toggle("paper-invert", "Invert", self.invert).in_group("Paper"),
+        ]
+    }
+
+    // Retorna false para chaves que nao sao do papel.
+    pub fn set_param(&mut self, key: &str, value: f64) -> bool {
+        match key {
+            "paper-scale" => self.scale = value.max(0.01),
+            "paper-depth" => self.depth = value.clamp(0.0, 1.0),
+            "paper-invert" => self.invert = value >= 0.5,
+            _ => return false,
+        }
+        if key != "paper-scale"
+            && let Err(e) = self.rebuild_mask()
+        {
+            eprintln!("Erro ao gerar a textura de papel: {}", e);
+        }
+        true
+    }
+
+    pub fn pattern(&self) -> Option<SurfacePattern> {
+        if self.depth <= 0.0 {
+            return None;
+        }
+        let pattern = SurfacePattern::create(self.mask.as_ref()?);
+        pattern.set_extend(Extend::Repeat);
+        let mut matrix = Matrix::identity();
+        matrix.scale(1.0 / self.scale, 1.0 / self.scale);
+        pattern.set_matrix(matrix);
+        Some(pattern)
+    }
+
+    // Alfa = 1 - profundidade * (1 - altura): vales do papel recebem menos tinta.
+    fn rebuild_mask(&mut self) -> Result<()> {
+        let Some(height_map) = self.height_map.as_ref() else {
+            return Ok(());
+        };
+        let (width, height) = height_map.dimensions();
+        let stride = Format::ARgb32.stride_for_width(width)?;
+        let mut data = vec![0u8; (stride as usize) * (height as usize)];
+        for (x, y, pixel) in height_map.enumerate_pixels() {
+            let mut h = pixel.0[0] as f64 / 255.0;
+            if self.invert {
+                h = 1.0 - h;
+            }
+            let alpha = 1.0 - self.depth * (1.0 - h);
+            let i = y as usize * stride as usize + x as usize * 4 + 3;
+            data[i] = (alpha * 255.0).round() as u8;
+        }
+
+        self.mask = Some(ImageSurface::create_for_data(
+            data,
+            Format::ARgb32,
+            width as i32,
+            height as i32,
+            stride,
+        )?);
+        Ok(())
+    }
+}
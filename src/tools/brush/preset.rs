@@ -0,0 +1,208 @@
+use super::{
+    blend_mode::BlendMode,
+    brush_definition::{BrushDefinition, BrushSettings, PipeSelection},
+    dynamics::BrushDynamics,
+    mypaint::MypaintSettings,
+    tip::{ProceduralTip, TipShape},
+};
+use crate::core::pixel_buffer::PixelBuffer;
+use anyhow::{Result, bail};
+use gtk::{
+    cairo::{Context, Format, ImageSurface},
+    glib::{self, KeyFile, KeyFileFlags},
+};
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use std::{io::Cursor, path::Path, rc::Rc};
+
+// Arquivo de preset: KeyFile com um ou mais pinceis (pacote).
+// [Presets] count=N; cada pincel i usa os grupos "Brush i" e "Dynamics i".
+pub const PRESET_EXTENSION: &str = "mpbrush";
+pub const BUNDLE_EXTENSION: &str = "mpbrushes";
+
+const THUMBNAIL_WIDTH: i32 = 96;
+const THUMBNAIL_HEIGHT: i32 = 48;
+
+pub fn is_preset(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|e| e == PRESET_EXTENSION || e == BUNDLE_EXTENSION)
+}
+
+pub fn save_file(path: &Path, brushes: &[&BrushDefinition]) -> Result<()> {
+    let key_file = KeyFile::new();
+    key_file.set_integer("Presets", "count", brushes.len() as i32);
+    for (i, brush) in brushes.iter().enumerate() {
+        write_preset(&key_file, i, brush)?;
+    }
+    key_file.save_to_file(path)?;
+    Ok(())
+}
+
+pub fn load_file(path: &Path) -> Result<Vec<BrushDefinition>> {
+    let key_file = KeyFile::new();
+    key_file.load_from_file(path, KeyFileFlags::NONE)?;
+
+    let count = key_file.integer("Presets", "count")?;
+    (0..count.max(0) as usize)
+        .map(|i| read_preset(&key_file, i))
+        .collect()
+}
+
+fn write_preset(key_file: &KeyFile, index: usize, brush: &BrushDefinition) -> Result<()> {
+    let group = format!("Brush {}", index);
+    let settings = brush.settings().clone();
+
+    key_file.set_string(&group, "name", &brush.get_name());
+    let numbers = [
+        ("size", settings.size),
+        ("spacing", settings.spacing),
+        ("opacity", settings.opacity),
+        ("flow", settings.flow),
+        ("hardness", settings.hardness),
+        ("airbrush-rate", settings.airbrush_rate),
+    ];
+    for (key, value) in numbers {
+        if let Some(value) = value {
+            key_file.set_double(&group, key, value);
+        }
+    }
+    if let Some(mode) = settings.blend_mode {
+        key_file.set_string(&group, "blend-mode", mode.name());
+    }
+
+    if let Some(tip) = brush.tip() {
+        key_file.set_string(&group, "tip", tip.shape.name());
+        key_file.set_double(&group, "roundness", tip.roundness);
+        key_file.set_double(&group, "tip-angle", tip.angle);
+    }
+    if let Some(mypaint) = brush.mypaint_settings() {
+        let encoded = glib::base64_encode(mypaint.source().as_bytes());
+        key_file.set_string(&group, "mypaint", &encoded);
+    }
+    key_file.set_string(&group, "selection", brush.selection().name());
+    let textures = brush.textures();
+    key_file.set_integer(&group, "textures", textures.len() as i32);
+    for (i, texture) in textures.iter().enumerate() {
+        let png = surface_to_png(texture)?;
+        key_file.set_string(&group, &format!("texture{}", i), &glib::base64_encode(&png));
+    }
+
+    if let Some(dynamics) = settings.dynamics {
+        let group = format!("Dynamics {}", index);
+        for param in dynamics.params() {
+            key_file.set_double(&group, param.key, param.value);
+        }
+    }
+    Ok(())
+}
+
+fn read_preset(key_file: &KeyFile, index: usize) -> Result<BrushDefinition> {
+    let group = format!("Brush {}", index);
+    let name = key_file.string(&group, "name")?;
+
+    let count = key_file.integer(&group, "textures")?;
+    let mut frames = Vec::new();
+    for i in 0..count.max(0) {
+        let encoded = key_file.string(&group, &format!("texture{}", i))?;
+        frames.push(image::load_from_memory(&glib::base64_decode(&encoded))?);
+    }
+    let selection = key_file
+        .string(&group, "selection")
+        .map(|s| PipeSelection::from_name(&s))
+        .unwrap_or(PipeSelection::Constant);
+
+    let number = |key: &str| key_file.double(&group, key).ok();
+    let blend_mode = match key_file.string(&group, "blend-mode") {
+        Ok(name) => match BlendMode::from_name(&name) {
+            Some(mode) => Some(mode),
+            None => bail!("Modo de mistura desconhecido: {}", name),
+        },
+        Err(_) => None,
+    };
+
+    let dynamics_group = format!("Dynamics {}", index);
+    let dynamics = key_file.has_group(&dynamics_group).then(|| {
+        let mut dynamics = BrushDynamics::new();
+        for key in key_file.keys(&dynamics_group).unwrap_or_default() {
+            if let Ok(value) = key_file.double(&dynamics_group, &key) {
+                dynamics.set_param(&key, value);
+            }
+        }
+        dynamics
+    });
+
+    let mut brush = if let Ok(encoded) = key_file.string(&group, "mypaint") {
+        let source = glib::base64_decode(&encoded);
+        let settings = MypaintSettings::parse(&String::from_utf8_lossy(&source))?;
+        BrushDefinition::mypaint(&name, Rc::new(settings))
+    } else {
+        match key_file.string(&group, "tip") {
+            Ok(shape) => {
+                let Some(shape) = TipShape::from_name(&shape) else {
+                    bail!("Ponta desconhecida: {}", shape);
+                };
+                let mut tip = ProceduralTip::new(shape);
+                tip.hardness = number("hardness").unwrap_or(tip.hardness);
+                tip.roundness = number("roundness").unwrap_or(tip.roundness);
+                tip.angle = number("tip-angle").unwrap_or(tip.angle);
+                BrushDefinition::procedural(&name, tip)
+            }
+            Err(_) => BrushDefinition::from_frames(&name, frames, selection)?,
+        }
+    };
+    brush.set_settings(BrushSettings {
+        size: number("size"),
+        spacing: number("spacing"),
+        opacity: number("opacity"),
+        flow: number("flow"),
+        hardness: number("hardness"),
+        blend_mode,
+        airbrush_rate: number("airbrush-rate"),
+        dynamics,
+    });
+    Ok(brush)
+}
+
+// Traco curto em "S" para a miniatura do painel.
+pub fn thumbnail(brush: &BrushDefinition) -> Result<ImageSurface> {
+    let surface = ImageSurface::create(Format::ARgb32, THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT)?;
+    let ctx = Context::new(&surface)?;
+    ctx.set_source_rgb(1.0, 1.0, 1.0);
+    ctx.paint()?;
+
+    let size = THUMBNAIL_HEIGHT as f64 * 0.4;
+    let margin = size;
+    let steps = 48;
+    for i in 0..=steps {
+        let t = i as f64 / steps as f64;
+        let x = margin + (THUMBNAIL_WIDTH as f64 - 2.0 * margin) * t;
+        let y = THUMBNAIL_HEIGHT as f64 / 2.0 * (1.0 - 0.5 * (t * std::f64::consts::TAU).sin());
+        let stamp = brush.render_tip(size * (0.4 + 0.6 * t))?;
+        ctx.set_source_rgba(0.0, 0.0, 0.0, 0.5);
+        ctx.mask_surface(
+            &stamp,
+            x - stamp.width() as f64 / 2.0,
+            y - stamp.height() as f64 / 2.0,
+        )?;
+    }
+    drop(ctx);
+    Ok(surface)
+}
+
+fn surface_to_png(surface: &ImageSurface) -> Result<Vec<u8>> {
+    let buffer = PixelBuffer::from_image(surface)?;
+    let mut pixels = Vec::with_capacity((buffer.width * buffer.height * 4) as usize);
+    for y in 0..buffer.height {
+        for x in 0..buffer.width {
+            let c = buffer.get(x, y);
+            pixels.extend(c.map(|v| (v * 255.0).round().clamp(0.0, 255.0) as u8));
+        }
+    }
+    let Some(image) = RgbaImage::from_raw(buffer.width as u32, buffer.height as u32, pixels) else {
+        bail!("Falha ao converter a superficie");
+    };
+
+    let mut png = Cursor::new(Vec::new());
+    DynamicImage::ImageRgba8(image).write_to(&mut png, ImageFormat::Png)?;
+    Ok(png.into_inner())
+}
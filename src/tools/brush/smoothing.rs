@@ -0,0 +1,143 @@
+use crate::geometry::Point;
+use std::collections::VecDeque;
+
+// Amostra do traco: posicao na imagem e pressao.
+pub type StrokeSample = (Point, f64);
+
+// Distancia maxima entre pontos gerados pela spline.
+const SPLINE_STEP: f64 = 2.0;
+
+pub struct StrokeSmoother {
+    pub lazy_distance: f64,
+    pub average_window: usize,
+    pub spline: bool,
+    raw: VecDeque<StrokeSample>,
+    rope: Option<StrokeSample>,
+    history: VecDeque<StrokeSample>,
+}
+
+impl StrokeSmoother {
+    pub fn new() -> Self {
+        StrokeSmoother {
+            lazy_distance: 0.0,
+            average_window: 1,
+            spline: true,
+            raw: VecDeque::new(),
+            rope: None,
+            history: VecDeque::new(),
+        }
+    }
+
+    pub fn begin(&mut self, sample: StrokeSample) {
+        self.raw.clear();
+        self.history.clear();
+        self.raw.push_back(sample);
+        self.rope = Some(sample);
+        // Duplica o primeiro ponto para a Catmull-Rom ter p0.
+        self.history.push_back(sample);
+        self.history.push_back(sample);
+    }
+
+    // Retorna os pontos a carimbar a partir do ultimo ponto ja emitido.
+    pub fn push(&mut self, sample: StrokeSample) -> Vec<StrokeSample> {
+        let averaged = self.average(sample);
+        let Some(stabilized) = self.pull_rope(averaged) else {
+            return Vec::new();
+        };
+
+        if !self.spline {
+            return vec![stabilized];
+        }
+
+        self.history.push_back(stabilized);
+        if self.history.len() < 4 {
+            return Vec::new();
+        }
+        let points = self.spline_segment();
+        self.history.pop_front();
+        points
+    }
+
+    // Fecha o traco, emitindo o ultimo segmento pendente da spline.
+    pub fn finish(&mut self) -> Vec<StrokeSample> {
+        if !self.spline || self.history.len() < 3 {
+            return Vec::new();
+        }
+        if let Some(&last) = self.history.back() {
+            self.history.push_back(last);
+        }
+        while self.history.len() > 4 {
+            self.history.pop_front();
+        }
+        let points = self.spline_segment();
+        self.history.clear();
+        points
+    }
+
+    fn average(&mut self, sample: StrokeSample) -> StrokeSample {
+        let window = self.average_window.max(1);
+        self.raw.push_back(sample);
+        while self.raw.len() > window {
+            self.raw.pop_front();
+        }
+
+        let n = self.raw.len() as f64;
+        let (sum, pressure) = self
+            .raw
+            .iter()
+            .fold((Point::ZERO, 0.0), |(acc, pressure), (p, pr)| {
+                (Point::new(acc.x + p.x, acc.y + p.y), pressure + pr)
+            });
+        (Point::new(sum.x / n, sum.y / n), pressure / n)
+    }
+
+    // "Lazy mouse": o pincel so anda quando a corda esta esticada.
+    fn pull_rope(&mut self, target: StrokeSample) -> Option<StrokeSample> {
+        let Some((pos, _)) = self.rope else {
+            self.rope = Some(target);
+            return Some(target);
+        };
+        if self.lazy_distance <= 0.0 {
+            self.rope = Some(target);
+            return Some(target);
+        }
+
+        let dx = target.0.x - pos.x;
+        let dy = target.0.y - pos.y;
+        let distance = dx.hypot(dy);
+        if distance <= self.lazy_distance {
+            return None;
+        }
+
+        let t = (distance - self.lazy_distance) / distance;
+        let moved = (Point::new(pos.x + dx * t, pos.y + dy * t), target.1);
+        self.rope = Some(moved);
+        Some(moved)
+    }
+
+    fn spline_segment(&self) -> Vec<StrokeSample> {
+        let [p0, p1, p2, p3] = [0, 1, 2, 3].map(|i| self.history[i]);
+        let chord = (p2.0.x - p1.0.x).hypot(p2.0.y - p1.0.y);
+        let steps = ((chord / SPLINE_STEP).ceil() as usize).max(1);
+
+        (1..=steps)
+            .map(|i| {
+                let t = i as f64 / steps as f64;
+                let point = Point::new(
+                    catmull_rom(p0.0.x, p1.0.x, p2.0.x, p3.0.x, t),
+                    catmull_rom(p0.0.y, p1.0.y, p2.0.y, p3.0.y, t),
+                );
+                (point, p1.1 + (p2.1 - p1.1) * t)
+            })
+            .collect()
+    }
+}
+
+fn catmull_rom(p0: f64, p1: f64, p2: f64, p3: f64, t: f64) -> f64 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * ((2.0 * p1)
+        + (-p0 + p2) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (-p0 + 3.0 * p1 - 3.0 * p2 + p3) * t3)
+}
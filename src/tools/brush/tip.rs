@@ -0,0 +1,95 @@
+use anyhow::Result;
+use gtk::cairo::{Format, ImageSurface};
+use std::f64::consts::SQRT_2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TipShape {
+    Circle,
+    Square,
+    Diamond,
+}
+
+impl TipShape {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TipShape::Circle => "circle",
+            TipShape::Square => "square",
+            TipShape::Diamond => "diamond",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "circle" => Some(TipShape::Circle),
+            "square" => Some(TipShape::Square),
+            "diamond" => Some(TipShape::Diamond),
+            _ => None,
+        }
+    }
+}
+
+// Ponta gerada analiticamente no tamanho pedido, sem textura.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProceduralTip {
+    pub shape: TipShape,
+    pub hardness: f64,
+    // Razao entre altura e largura (1 = redonda).
+    pub roundness: f64,
+    pub angle: f64,
+}
+
+impl ProceduralTip {
+    pub fn new(shape: TipShape) -> Self {
+        ProceduralTip {
+            shape,
+            hardness: 1.0,
+            roundness: 1.0,
+            angle: 0.0,
+        }
+    }
+
+    pub fn render(&self, thickness: f64, angle_deg: i32) -> Result<ImageSurface> {
+        let radius = (thickness / 2.0).max(0.5);
+        // Girado, o quadrado precisa da diagonal; circulo e losango cabem no raio.
+        let extent = match self.shape {
+            TipShape::Square => thickness * SQRT_2,
+            TipShape::Circle | TipShape::Diamond => thickness,
+        };
+        let size = (extent.ceil() as i32).max(1) + 2;
+        let stride = Format::ARgb32.stride_for_width(size as u32)?;
+        let mut data = vec![0u8; (stride * size) as usize];
+
+        let theta = (self.angle + angle_deg as f64).to_radians();
+        let (sin, cos) = theta.sin_cos();
+        let roundness = self.roundness.clamp(0.01, 1.0);
+        // Borda dura ainda recebe 1px de antialias.
+        let fade = (1.0 - self.hardness.clamp(0.0, 1.0)).max(1.0 / radius);
+        let center = size as f64 / 2.0;
+
+        for py in 0..size {
+            for px in 0..size {
+                let x = (px as f64 + 0.5 - center) / radius;
+                let y = (py as f64 + 0.5 - center) / radius;
+                let u = x * cos + y * sin;
+                let v = (-x * sin + y * cos) / roundness;
+                let distance = match self.shape {
+                    TipShape::Circle => u.hypot(v),
+                    TipShape::Square => u.abs().max(v.abs()),
+                    TipShape::Diamond => u.abs() + v.abs(),
+                };
+
+                let t = ((1.0 - distance) / fade).clamp(0.0, 1.0);
+                let alpha = t * t * (3.0 - 2.0 * t);
+                data[(py * stride + px * 4 + 3) as usize] = (alpha * 255.0).round() as u8;
+            }
+        }
+
+        Ok(ImageSurface::create_for_data(
+            data,
+            Format::ARgb32,
+            size,
+            size,
+            stride,
+        )?)
+    }
+}
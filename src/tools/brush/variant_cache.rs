@@ -0,0 +1,246 @@
+use gtk::cairo::ImageSurface;
+use std::{collections::HashMap, fmt};
+
+// Orcamento padrao de memoria por pincel.
+const DEFAULT_BUDGET: usize = 16 * 1024 * 1024;
+// Abaixo deste tamanho as variantes usam quartos de pixel.
+const FINE_BUCKET_LIMIT: f64 = 16.0;
+const FINE_BUCKET_STEPS: f64 = 4.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VariantKey {
+    bucket: i32,
+    angle_deg: i32,
+    frame: usize,
+}
+
+impl VariantKey {
+    pub fn new(thickness: f64, angle_deg: i32, frame: usize) -> Self {
+        let bucket = if thickness < FINE_BUCKET_LIMIT {
+            (thickness * FINE_BUCKET_STEPS).round() as i32
+        } else {
+            (thickness.round() * FINE_BUCKET_STEPS) as i32
+        };
+        VariantKey {
+            bucket: bucket.max(1),
+            angle_deg,
+            frame,
+        }
+    }
+
+    // Tamanho efetivamente renderizado para este balde.
+    pub fn thickness(&self) -> f64 {
+        self.bucket as f64 / FINE_BUCKET_STEPS
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+    pub bytes: usize,
+}
+
+impl CacheStats {
+    pub fn add(&mut self, other: &CacheStats) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.evictions += other.evictions;
+        self.entries += other.entries;
+        self.bytes += other.bytes;
+    }
+}
+
+impl fmt::Display for CacheStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} variants, {} KiB, {} hits, {} misses, {} evicted",
+            self.entries,
+            self.bytes / 1024,
+            self.hits,
+            self.misses,
+            self.evictions
+        )
+    }
+}
+
+struct Entry {
+    surface: ImageSurface,
+    bytes: usize,
+    last_used: u64,
+}
+
+// Cache LRU de variantes (tamanho, angulo, quadro) limitado em bytes.
+pub struct VariantCache {
+    entries: HashMap<VariantKey, Entry>,
+    budget: usize,
+    clock: u64,
+    stats: CacheStats,
+}
+
+impl VariantCache {
+    pub fn new() -> Self {
+        VariantCache {
+            entries: HashMap::new(),
+            budget: DEFAULT_BUDGET,
+            clock: 0,
+            stats: CacheStats::default(),
+        }
+    }
+
+    // Marca o uso da variante; retorna false quando ela precisa ser gerada.
+    pub fn touch(&mut self, key: &VariantKey) -> bool {
+        self.clock += 1;
+        match self.entries.get_mut(key) {
+            Some(entry) => {
+                entry.last_used = self.clock;
+                self.stats.hits += 1;
+                true
+            }
+            None => {
+                self.stats.misses += 1;
+                false
+            }
+        }
+    }
+
+    pub fn insert(&mut self, key: VariantKey, surface: ImageSurface) {
+        let bytes = (surface.stride() * surface.height()).max(0) as usize;
+        if let Some(old) = self.entries.remove(&key) {
+            self.stats.bytes -= old.bytes;
+        }
+        self.entries.insert(
+            key,
+            Entry {
+                surface,
+                bytes,
+                last_used: self.clock,
+            },
+        );
+        self.stats.bytes += bytes;
+        self.evict(&key);
+    }
+
+    pub fn get(&self, key: &VariantKey) -> Option<&ImageSurface> {
+        self.entries.get(key).map(|e| &e.surface)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.stats.bytes = 0;
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.len(),
+            ..self.stats
+        }
+    }
+
+    // Remove as menos usadas ate caber no orcamento, preservando `keep`.
+    fn evict(&mut self, keep: &VariantKey) {
+        while self.stats.bytes > self.budget && self.entries.len() > 1 {
+            let oldest = self
+                .entries
+                .iter()
+                .filter(|(key, _)| *key != keep)
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| *key);
+            let Some(oldest) = oldest else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&oldest) {
+                self.stats.bytes -= entry.bytes;
+                self.stats.evictions += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gtk::cairo::Format;
+
+    // Superficie 8x8 ARGB: 256 bytes.
+    fn surface() -> ImageSurface {
+        ImageSurface::create(Format::ARgb32, 8, 8).unwrap()
+    }
+
+    fn cache(entries: usize) -> VariantCache {
+        let mut cache = VariantCache::new();
+        cache.budget = entries * 256;
+        cache
+    }
+
+    // Caminho usado pelo pincel: consulta e gera na falta.
+    fn use_key(cache: &mut VariantCache, key: VariantKey) {
+        if !cache.touch(&key) {
+            cache.insert(key, surface());
+        }
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = cache(3);
+        let [a, b, c, d] = [20.0, 21.0, 22.0, 23.0].map(|size| VariantKey::new(size, 0, 0));
+        use_key(&mut cache, a);
+        use_key(&mut cache, b);
+        use_key(&mut cache, c);
+        use_key(&mut cache, a);
+        use_key(&mut cache, d);
+
+        assert!(cache.get(&b).is_none());
+        assert!([a, c, d].iter().all(|key| cache.get(key).is_some()));
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 1,
+                misses: 4,
+                evictions: 1,
+                entries: 3,
+                bytes: 3 * 256,
+            }
+        );
+    }
+
+    #[test]
+    fn keeps_new_entry_over_budget() {
+        let mut cache = cache(1);
+        let (a, b) = (VariantKey::new(20.0, 0, 0), VariantKey::new(20.0, 90, 0));
+        use_key(&mut cache, a);
+        cache.touch(&b);
+        cache.insert(b, ImageSurface::create(Format::ARgb32, 16, 16).unwrap());
+
+        assert!(cache.get(&a).is_none());
+        assert!(cache.get(&b).is_some());
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn clear_keeps_counters() {
+        let mut cache = cache(4);
+        use_key(&mut cache, VariantKey::new(20.0, 0, 0));
+        cache.clear();
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.bytes, stats.misses), (0, 0, 1));
+    }
+
+    #[test]
+    fn buckets_are_finer_for_small_sizes() {
+        // Pequenos: quartos de pixel.
+        assert_ne!(VariantKey::new(3.1, 0, 0), VariantKey::new(3.2, 0, 0));
+        assert_eq!(VariantKey::new(3.1, 0, 0).thickness(), 3.0);
+        // Grandes: pixel inteiro.
+        assert_eq!(VariantKey::new(30.2, 0, 0), VariantKey::new(29.8, 0, 0));
+        assert_eq!(VariantKey::new(30.2, 0, 0).thickness(), 30.0);
+        // Nunca zero.
+        assert_eq!(VariantKey::new(0.01, 0, 0).thickness(), 0.25);
+        // Angulo e quadro fazem parte da chave.
+        assert_ne!(VariantKey::new(20.0, 0, 0), VariantKey::new(20.0, 15, 0));
+        assert_ne!(VariantKey::new(20.0, 0, 0), VariantKey::new(20.0, 0, 1));
+    }
+}
@@ -0,0 +1,297 @@
+use crate::{
+    core::{canvas::Canvas, event::AppEvents, pixel_buffer::PixelBuffer},
+    geometry::Point,
+    program::ProgramState,
+};
+use gtk::cairo::Context;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CloneMode {
+    Clone,
+    Heal,
+}
+
+pub struct CloneTool {
+    mode: CloneMode,
+    aligned: bool,
+    source: Option<Point>,
+    offset: Option<Point>,
+    snapshot: Option<PixelBuffer>,
+    is_drawing: bool,
+    last_point: Point,
+    thickness: f64,
+    spacing: f64,
+}
+
+impl CloneTool {
+    pub fn new(mode: CloneMode) -> Self {
+        CloneTool {
+            mode,
+            aligned: true,
+            source: None,
+            offset: None,
+            snapshot: None,
+            is_drawing: false,
+            last_point: Point::ZERO,
+            thickness: 30.0,
+            spacing: 0.25,
+        }
+    }
+
+    pub fn set_source(&mut self, source: Point) {
+        self.source = Some(source);
+        self.offset = None;
+    }
+
+    pub fn set_aligned(&mut self, aligned: bool) {
+        self.aligned = aligned;
+        self.offset = None;
+    }
+
+    pub fn on_event(&mut self, events: AppEvents, canvas: &mut Canvas, state: &mut ProgramState) {
+        match events {
+            AppEvents::MouseDown(event) => {
+                // Sem origem (Ctrl+clique) o clique nao faz nada.
+                let Some(source) = self.source else {
+                    return;
+                };
+                let Some(image) = canvas.image() else {
+                    return;
+                };
+
+                let image_point = canvas.screen_to_canvas_coords(event.point);
+                // Alinhado: o deslocamento e mantido entre os tracos.
+                // Nao alinhado: cada traco recomeca na origem.
+                if self.offset.is_none() || !self.aligned {
+                    self.offset = Some(Point::new(
+                        source.x - image_point.x,
+                        source.y - image_point.y,
+                    ));
+                }
+                self.snapshot = PixelBuffer::from_image(image).ok();
+                self.last_point = image_point;
+                self.is_drawing = true;
+
+                if let Some(ctx) = canvas.get_image_context() {
+                    self.draw_stamp(&ctx, canvas, image_point);
+                    state.request_paint();
+                }
+            }
+            AppEvents::MouseMove(event) => {
+                if !self.is_drawing {
+                    return;
+                }
+
+                if let Some(ctx) = canvas.get_image_context() {
+                    let image_point = canvas.screen_to_canvas_coords(event.point);
+                    self.draw_stamps(&ctx, canvas, self.last_point, image_point);
+                    self.last_point = image_point;
+                    state.request_paint();
+                }
+            }
+            AppEvents::MouseUp(_) => {
+                self.is_drawing = false;
+                self.snapshot = None;
+                state.stop_request_paint();
+            }
+            _ => {}
+        }
+    }
+
+    fn draw_stamps(&mut self, ctx: &Context, canvas: &Canvas, start: Point, end: Point) {
+        let dx = end.x - start.x;
+        let dy = end.y - start.y;
+        let distance = dx.hypot(dy);
+        let spacing_use = (self.thickness * self.spacing).max(1.0);
+        let steps = (distance / spacing_use).ceil() as u32;
+
+        for i in 1..=steps {
+            let t = (i as f64) / (steps as f64);
+            let p = Point::new(start.x + dx * t, start.y + dy * t);
+            self.draw_stamp(ctx, canvas, p);
+        }
+    }
+
+    fn draw_stamp(&self, ctx: &Context, canvas: &Canvas, center: Point) {
+        let (Some(image), Some(snapshot), Some(offset)) =
+            (canvas.image(), self.snapshot.as_ref(), self.offset)
+        else {
+            return;
+        };
+
+        let radius = self.thickness / 2.0;
+        let x = (center.x - radius).floor() as i32;
+        let y = (center.y - radius).floor() as i32;
+        let size = self.thickness.ceil() as i32 + 2;
+        let Ok(mut dest) = PixelBuffer::from_surface(image, x, y, size, size) else {
+            return;
+        };
+        if !self.blend_stamp(snapshot, &mut dest, center, offset) {
+            return;
+        }
+        if let Err(e) = dest.paint_on(ctx) {
+            eprintln!("Erro ao clonar: {}", e);
+        }
+    }
+
+    // Mistura em `dest` a origem deslocada por `offset`, com borda suave.
+    fn blend_stamp(
+        &self,
+        snapshot: &PixelBuffer,
+        dest: &mut PixelBuffer,
+        center: Point,
+        offset: Point,
+    ) -> bool {
+        let radius = self.thickness / 2.0;
+        let (x, y) = (dest.x, dest.y);
+        let dx = offset.x.round() as i32;
+        let dy = offset.y.round() as i32;
+
+        let mut weights = Vec::with_capacity((dest.width * dest.height) as usize);
+        for py in y..y + dest.height {
+            for px in x..x + dest.width {
+                let distance = (px as f64 + 0.5 - center.x).hypot(py as f64 + 0.5 - center.y);
+                let w = Self::falloff(distance / radius);
+                weights.push((px, py, w as f32));
+            }
+        }
+
+        // Cura: textura da origem com a cor media do destino.
+        let (shift_src, shift_dst) = match self.mode {
+            CloneMode::Clone => ([0.0; 3], [0.0; 3]),
+            CloneMode::Heal => {
+                let mut src_sum = [0.0f32; 3];
+                let mut dst_sum = [0.0f32; 3];
+                let mut total = 0.0f32;
+                for &(px, py, w) in &weights {
+                    if w <= 0.0 {
+                        continue;
+                    }
+                    let s = snapshot.get(px + dx, py + dy);
+                    let d = dest.get(px, py);
+                    for c in 0..3 {
+                        src_sum[c] += s[c] * w;
+                        dst_sum[c] += d[c] * w;
+                    }
+                    total += w;
+                }
+                if total <= 0.0 {
+                    return false;
+                }
+                (src_sum.map(|v| v / total), dst_sum.map(|v| v / total))
+            }
+        };
+
+        for &(px, py, w) in &weights {
+            if w <= 0.0 || !snapshot.contains(px + dx, py + dy) {
+                continue;
+            }
+            let mut s = snapshot.get(px + dx, py + dy);
+            if self.mode == CloneMode::Heal {
+                for c in 0..3 {
+                    s[c] = (s[c] - shift_src[c] + shift_dst[c]).clamp(0.0, 1.0);
+                }
+                s[3] = dest.get(px, py)[3].max(s[3]);
+            }
+
+            let s = [s[0] * s[3], s[1] * s[3], s[2] * s[3], s[3]];
+            let d = dest.get_premultiplied(px, py);
+            let out = [0, 1, 2, 3].map(|c| d[c] + (s[c] - d[c]) * w);
+            dest.set_premultiplied(px, py, out);
+        }
+        true
+    }
+
+    fn falloff(t: f64) -> f64 {
+        if t >= 1.0 {
+            0.0
+        } else if t <= 0.5 {
+            1.0
+        } else {
+            let t = (1.0 - t) / 0.5;
+            t * t * (3.0 - 2.0 * t)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Buffer opaco com a cor dada por pixel.
+    fn filled(x: i32, y: i32, size: i32, color: impl Fn(i32, i32) -> [f32; 3]) -> PixelBuffer {
+        let mut buffer = PixelBuffer::new(x, y, size, size);
+        for py in y..y + size {
+            for px in x..x + size {
+                let [r, g, b] = color(px, py);
+                buffer.set(px, py, [r, g, b, 1.0]);
+            }
+        }
+        buffer
+    }
+
+    fn tool(mode: CloneMode) -> CloneTool {
+        let mut tool = CloneTool::new(mode);
+        tool.thickness = 10.0;
+        tool
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 0.02
+    }
+
+    #[test]
+    fn clone_copies_source_inside_radius_only() {
+        // Metade esquerda vermelha, direita azul.
+        let color = |x: i32, _| {
+            if x < 20 {
+                [1.0, 0.0, 0.0]
+            } else {
+                [0.0, 0.0, 1.0]
+            }
+        };
+        let snapshot = filled(0, 0, 40, color);
+        let mut dest = filled(24, 14, 12, color);
+
+        let tool = tool(CloneMode::Clone);
+        let center = Point::new(30.0, 20.0);
+        assert!(tool.blend_stamp(&snapshot, &mut dest, center, Point::new(-20.0, 0.0)));
+
+        let inside = dest.get(30, 20);
+        assert!(close(inside[0], 1.0) && close(inside[2], 0.0));
+        // Canto fora do raio continua com o destino.
+        let outside = dest.get(24, 14);
+        assert!(close(outside[0], 0.0) && close(outside[2], 1.0));
+    }
+
+    #[test]
+    fn heal_moves_source_texture_to_destination_tone() {
+        // Origem: listras escuras (0.1/0.3). Destino: cinza claro liso.
+        let snapshot = filled(0, 0, 40, |x, _| {
+            let v = if x % 2 == 0 { 0.1 } else { 0.3 };
+            [v; 3]
+        });
+        let mut dest = filled(24, 14, 12, |_, _| [0.8; 3]);
+
+        let tool = tool(CloneMode::Heal);
+        let center = Point::new(30.0, 20.0);
+        assert!(tool.blend_stamp(&snapshot, &mut dest, center, Point::new(-20.0, 0.0)));
+
+        let (even, odd) = (dest.get(30, 20)[0], dest.get(31, 20)[0]);
+        assert!(close(odd - even, 0.2), "textura perdida: {} {}", even, odd);
+        assert!(
+            (even + odd) / 2.0 > 0.7,
+            "tom do destino perdido: {} {}",
+            even,
+            odd
+        );
+    }
+
+    #[test]
+    fn falloff_is_flat_then_smooth() {
+        assert_eq!(CloneTool::falloff(0.0), 1.0);
+        assert_eq!(CloneTool::falloff(0.5), 1.0);
+        assert!((CloneTool::falloff(0.75) - 0.5).abs() < 1e-9);
+        assert_eq!(CloneTool::falloff(1.0), 0.0);
+    }
+}
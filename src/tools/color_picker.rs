@@ -0,0 +1,55 @@
+use crate::{
+    core::{canvas::Canvas, color::Color, event::AppEvents, pixel_buffer::PixelBuffer},
+    geometry::Point,
+    program::ProgramState,
+};
+
+// Conta-gotas: arrastando, a cor sob o ponteiro vira a cor de frente.
+pub struct ColorPickerTool {
+    picking: bool,
+    picked: Option<Color>,
+}
+
+impl ColorPickerTool {
+    pub fn new() -> Self {
+        ColorPickerTool {
+            picking: false,
+            picked: None,
+        }
+    }
+
+    pub fn take_picked(&mut self) -> Option<Color> {
+        self.picked.take()
+    }
+
+    fn pick(&mut self, canvas: &Canvas, screen_point: Point) {
+        let Some(image) = canvas.image() else {
+            return;
+        };
+        let point = canvas.screen_to_canvas_coords(screen_point);
+        let (x, y) = (point.x.floor() as i32, point.y.floor() as i32);
+        if x < 0 || y < 0 || x >= image.width() || y >= image.height() {
+            return;
+        }
+        let Ok(buffer) = PixelBuffer::from_surface(image, x, y, 1, 1) else {
+            return;
+        };
+        let [r, g, b, a] = buffer.get(x, y);
+        // Pixel transparente nao tem cor para pegar.
+        if a > 0.0 {
+            self.picked = Some(Color::new(r as f64, g as f64, b as f64));
+        }
+    }
+
+    pub fn on_event(&mut self, events: AppEvents, canvas: &mut Canvas, _state: &mut ProgramState) {
+        match events {
+            AppEvents::MouseDown(event) => {
+                self.picking = true;
+                self.pick(canvas, event.point);
+            }
+            AppEvents::MouseMove(event) if self.picking => self.pick(canvas, event.point),
+            AppEvents::MouseUp(_) => self.picking = false,
+            _ => {}
+        }
+    }
+}
@@ -10,3 +10,14 @@ pub use rotate::RotateTool;
 mod brush;
 pub use brush::BrushTool;
 
+mod clone;
+pub use clone::{CloneMode, CloneTool};
+
+mod color_picker;
+pub use color_picker::ColorPickerTool;
+
+mod assistant;
+pub use assistant::AssistantTool;
+
+mod select;
+pub use select::SelectTool;
@@ -13,11 +13,12 @@ impl PanTool {
     }
     pub fn on_event(&mut self, events: AppEvents, canvas: &mut Canvas, state: &mut ProgramState) {
         match events {
-            AppEvents::MouseDown(point) => {
-                self.last_point = Some(point);
+            AppEvents::MouseDown(event) => {
+                self.last_point = Some(event.point);
                 state.request_paint();
             }
-            AppEvents::MouseMove(point) => {
+            AppEvents::MouseMove(event) => {
+                let point = event.point;
                 if let Some(last) = self.last_point {
                     let dx = point.x - last.x;
                     let dy = point.y - last.y;
@@ -0,0 +1,52 @@
+use crate::{
+    core::{
+        canvas::Canvas,
+        event::{AppEvents, Key, KeyEvent},
+    },
+    geometry::{Point, Rect},
+    program::ProgramState,
+};
+
+// Selecao retangular: arrastar define a regiao, clique simples ou Esc limpa.
+pub struct SelectTool {
+    start: Option<Point>,
+}
+
+impl SelectTool {
+    pub fn new() -> Self {
+        SelectTool { start: None }
+    }
+
+    pub fn on_event(&mut self, events: AppEvents, canvas: &mut Canvas, state: &mut ProgramState) {
+        match events {
+            AppEvents::MouseDown(event) => {
+                self.start = Some(canvas.screen_to_canvas_coords(event.point));
+                canvas.set_selection(None);
+                state.request_paint();
+            }
+            AppEvents::MouseMove(event) => {
+                if let Some(start) = self.start {
+                    let end = canvas.screen_to_canvas_coords(event.point);
+                    canvas.set_selection(Some(Self::rect(start, end)));
+                    state.request_paint();
+                }
+            }
+            AppEvents::MouseUp(_) => self.start = None,
+            AppEvents::KeyDown(KeyEvent {
+                key: Key::Escape, ..
+            }) => {
+                canvas.set_selection(None);
+                state.request_paint();
+            }
+            _ => {}
+        }
+    }
+
+    fn rect(a: Point, b: Point) -> Rect {
+        let x0 = a.x.min(b.x).floor() as i32;
+        let y0 = a.y.min(b.y).floor() as i32;
+        let x1 = a.x.max(b.x).ceil() as i32;
+        let y1 = a.y.max(b.y).ceil() as i32;
+        Rect::new(x0, y0, x1 - x0, y1 - y0)
+    }
+}
@@ -34,9 +34,18 @@ impl ZoomTool {
         }
     }
 
+    // Como ferramenta ativa: clique aproxima, Alt+clique afasta.
+    pub fn on_click(&mut self, events: AppEvents, canvas: &mut Canvas, state: &mut ProgramState) {
+        if let AppEvents::MouseDown(event) = events {
+            self.point = event.point;
+            self.apply_zoom(if event.modifiers.alt { 1.0 } else { -1.0 }, canvas);
+            state.request_paint();
+        }
+    }
+
     pub fn on_event(&mut self, events: AppEvents, canvas: &mut Canvas, state: &mut ProgramState) {
         match events {
-            AppEvents::MouseMove(point) => self.point = point,
+            AppEvents::MouseMove(event) => self.point = event.point,
             AppEvents::ScroolEvent { delta_y } => {
                 let zoom_factor = if delta_y < 0.0 { 1.1 } else { 1.0 / 1.1 };
                 let pre_zoom_x = (self.point.x - canvas.position.x) / canvas.zoom;